@@ -0,0 +1,134 @@
+extern crate chrono;
+use self::chrono::{Duration, Local};
+use crate::{favorites::Favorites, rm_structure::{Query, Sche}};
+
+pub const ALERTS_FILE_NAME: &str = "Alerts Fired.txt";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Sche,
+    Rule,
+    Stage,
+    Weapon,
+    King,
+    BigRun,
+}
+
+// One `Alert=` option, e.g. `Sche:CoopSche; King:Cohozuna|Horrorboros; BigRun`.
+// Every filter has to match, and any of the `|` separated values matches a filter
+#[derive(Clone)]
+pub struct AlertRule {
+    filters: Vec<(Field, Vec<String>)>,
+}
+
+impl std::str::FromStr for AlertRule {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filters = Vec::new();
+        for filter in s.split(';').map(|f| f.trim()).filter(|f| !f.is_empty()) {
+            let (key, values) = filter.split_once(':').unwrap_or((filter, ""));
+            let field = match key.trim().to_lowercase().as_str() {
+                "sche" => Field::Sche,
+                "rule" | "mode" => Field::Rule,
+                "stage" => Field::Stage,
+                "weapon" => Field::Weapon,
+                "king" => Field::King,
+                "bigrun" => Field::BigRun,
+                _ => return Err(format!("Unknown Alert Filter: {key}")),
+            };
+            let values: Vec<String> = values.split('|').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect();
+            if values.is_empty() && field != Field::BigRun {
+                return Err(format!("Alert Filter {key} Needs A Value"));
+            }
+            filters.push((field, values));
+        }
+        if filters.is_empty() {
+            return Err("Empty Alert".to_string());
+        }
+        Ok(AlertRule { filters })
+    }
+}
+
+impl AlertRule {
+    // Also used to filter the rotation history
+    pub fn matches(&self, sche_id: &str, event: &dyn Query) -> bool {
+        self.filters.iter().all(|(field, values)| {
+            let fields: &[&str] = match field {
+                Field::Sche => return values.iter().any(|v| v.eq_ignore_ascii_case(sche_id)),
+                Field::BigRun => return event.query("bigrun").is_some_and(|b| b == "1"),
+                Field::Rule => &["rule"],
+                Field::Stage => &["stage1", "stage2", "stage"],
+                Field::Weapon => &["weapon1", "weapon2", "weapon3", "weapon4"],
+                Field::King => &["king"],
+            };
+            fields.iter().filter_map(|f| event.query(f)).any(|found| values.iter().any(|v| v.eq_ignore_ascii_case(&found)))
+        })
+    }
+}
+
+// Rotations the alert options pick out, fired once each when they're about to start
+pub struct Alerts {
+    pub rules: Vec<AlertRule>,
+    pub lead_time: Duration,
+    // Keys of rotations that were already alerted, kept in ALERTS_FILE_NAME between sessions
+    pub fired: Vec<String>,
+}
+
+pub struct Alert {
+    pub key: String,
+    pub text: String,
+}
+
+impl Alerts {
+    // Rotations starting within the lead time, or already on, that match a rule or a favorite
+    // and haven't been alerted yet
+    pub fn due(&self, prefix: &str, schedules: &[Box<dyn Sche>], favorites: &Favorites) -> Vec<Alert> {
+        let now = Local::now();
+        let mut ret = Vec::new();
+        for sche in schedules {
+            let events = (0..).map_while(|i| sche.event(i));
+            for event in events {
+                let Some((start, _)) = event.run_time() else { continue };
+                if start - self.lead_time > now {
+                    continue;
+                }
+                let favorite = event.vs_setting().is_some_and(|s| favorites.matches(s));
+                if !favorite && !self.rules.iter().any(|r| r.matches(sche.get_id(), event)) {
+                    continue;
+                }
+                let key = format!("{prefix}|{}|{}", sche.get_id(), start.timestamp());
+                if self.fired.contains(&key) {
+                    continue;
+                }
+                let text = if start <= now {
+                    format!("{}: {} is on now", sche.get_title(), event.summary())
+                } else {
+                    format!("{}: {} starts at {}", sche.get_title(), event.summary(), start.format("%-I:%M%P"))
+                };
+                ret.push(Alert { key, text });
+            }
+        }
+        ret
+    }
+    // Drops keys of rotations that started over a day ago so the file doesn't grow forever
+    pub fn prune(&mut self) {
+        let cutoff = (Local::now() - Duration::days(1)).timestamp();
+        self.fired.retain(|key| key.rsplit('|').next().and_then(|t| t.parse::<i64>().ok()).is_some_and(|t| t > cutoff));
+    }
+    pub fn read_fired(path: &str) -> Vec<String> {
+        std::fs::read_to_string(path).unwrap_or_default().lines().map(|l| l.to_string()).collect()
+    }
+    // Every instance shares the file, so keys other skins saved since it was read are added
+    pub fn merge_fired(&mut self, path: &str) {
+        for key in Self::read_fired(path) {
+            if !self.fired.contains(&key) {
+                self.fired.push(key);
+            }
+        }
+    }
+    pub fn save_fired(&mut self, path: &str) -> Result<(), String> {
+        self.merge_fired(path);
+        self.prune();
+        std::fs::write(path, self.fired.join("\n")).map_err(|e| format!("Failed To Save Alerts: {e:?}"))
+    }
+}
@@ -0,0 +1,89 @@
+use crate::alerts::AlertRule;
+
+pub const REGIONS: [&str; 4] = ["US", "EU", "JP", "AP"];
+pub const LANGUAGES: [&str; 14] = ["de-DE", "en-GB", "en-US", "es-ES", "es-MX", "fr-CA", "fr-FR", "it-IT", "ja-JP", "ko-KR", "nl-NL", "ru-RU", "zh-CN", "zh-TW"];
+
+pub enum Command {
+    RefreshFile,
+    RepullData,
+    RedrawSche(String),
+    Next,
+    Prev,
+    Goto(usize),
+    Pin(Option<String>),
+    Export(ExportFormat),
+    ClearCache(CacheKind),
+    Status,
+    // Rotations in the last so many days that match an `Alert=` style filter
+    History(i64, AlertRule),
+    SetRegion(String),
+    SetLang(String),
+}
+
+pub enum ExportFormat {
+    Ics,
+    Json,
+    History,
+    Stats,
+}
+
+pub enum CacheKind {
+    Images,
+    Json,
+}
+
+impl std::str::FromStr for Command {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let args: Vec<&str> = s.split_whitespace().collect();
+        let (name, args) = args.split_first().ok_or("Empty Command".to_string())?;
+        let usage = |u: &str| format!("Usage: {u}");
+        match (name.to_lowercase().as_str(), args) {
+            ("refreshfile", []) => Ok(Command::RefreshFile),
+            ("repulldata", []) => Ok(Command::RepullData),
+            ("redrawsche", [id]) => Ok(Command::RedrawSche(id.to_string())),
+            ("redrawsche", _) => Err(usage("RedrawSche <id>")),
+            ("next", []) => Ok(Command::Next),
+            ("prev", []) => Ok(Command::Prev),
+            ("goto", [index]) => match index.parse::<usize>() {
+                Ok(i) if i > 0 => Ok(Command::Goto(i)),
+                _ => Err(format!("Invalid Index: {index}, Counting Starts At 1")),
+            },
+            ("goto", _) => Err(usage("Goto <index>")),
+            ("pin", []) => Ok(Command::Pin(None)),
+            ("pin", [id]) => Ok(Command::Pin(Some(id.to_string()))),
+            ("pin", _) => Err(usage("Pin [id]")),
+            ("export", [format]) => match format.to_lowercase().as_str() {
+                "ics" => Ok(Command::Export(ExportFormat::Ics)),
+                "json" => Ok(Command::Export(ExportFormat::Json)),
+                "history" => Ok(Command::Export(ExportFormat::History)),
+                "stats" => Ok(Command::Export(ExportFormat::Stats)),
+                _ => Err(format!("Unknown Export Format: {format}")),
+            },
+            ("export", _) => Err(usage("Export ics|json|history|stats")),
+            ("clearcache", [kind]) => match kind.to_lowercase().as_str() {
+                "images" => Ok(Command::ClearCache(CacheKind::Images)),
+                "json" => Ok(Command::ClearCache(CacheKind::Json)),
+                _ => Err(format!("Unknown Cache: {kind}")),
+            },
+            ("clearcache", _) => Err(usage("ClearCache images|json")),
+            ("status", []) => Ok(Command::Status),
+            // The filter has spaces in it, so it's everything after the day count
+            ("history", [days, filter @ ..]) if !filter.is_empty() => match days.parse::<i64>() {
+                Ok(d) if d > 0 => Ok(Command::History(d, filter.join(" ").parse()?)),
+                _ => Err(format!("Invalid Day Count: {days}")),
+            },
+            ("history", _) => Err(usage("History <days> <filter>")),
+            ("setregion", [region]) => REGIONS.iter().find(|r| r.eq_ignore_ascii_case(region))
+                .map(|r| Command::SetRegion(r.to_string()))
+                .ok_or(format!("Unknown Region: {region}, Expected One Of {}", REGIONS.join(", "))),
+            ("setregion", _) => Err(usage("SetRegion US|EU|JP|AP")),
+            ("setlang", [lang]) => LANGUAGES.iter().find(|l| l.eq_ignore_ascii_case(lang))
+                .map(|l| Command::SetLang(l.to_string()))
+                .ok_or(format!("Unknown Language: {lang}, Expected One Of {}", LANGUAGES.join(", "))),
+            ("setlang", _) => Err(usage("SetLang <language>")),
+            ("refreshfile" | "repulldata" | "next" | "prev" | "status", _) => Err(format!("{name} Takes No Arguments")),
+            _ => Err(format!("Unknown Command: {name}")),
+        }
+    }
+}
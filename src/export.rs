@@ -0,0 +1,79 @@
+extern crate chrono;
+extern crate serde;
+use self::serde::Serialize;
+use crate::rm_structure::Sche;
+
+pub const ICS_FILE_NAME: &str = "Splatoon 3 Schedules.ics";
+pub const JSON_FILE_NAME: &str = "Splatoon 3 Schedules.json";
+
+#[derive(Serialize)]
+struct ExportEvent {
+    schedule: String,
+    title: String,
+    start: i64,
+    end: i64,
+    summary: String,
+}
+
+// Every rotation that hasn't ended yet, in schedule order
+fn upcoming(schedules: &[Box<dyn Sche>]) -> Vec<ExportEvent> {
+    let now = chrono::Local::now();
+    let mut ret = Vec::new();
+    for sche in schedules {
+        let mut i = 0;
+        while let Some(event) = sche.event(i) {
+            if let Some((start, end)) = event.run_time().filter(|(_, end)| *end > now) {
+                ret.push(ExportEvent {
+                    schedule: sche.get_id().to_string(),
+                    title: sche.get_title().to_string(),
+                    start: start.timestamp(),
+                    end: end.timestamp(),
+                    summary: event.summary(),
+                });
+            }
+            i += 1;
+        }
+    }
+    ret
+}
+
+pub fn to_json(schedules: &[Box<dyn Sche>]) -> Result<String, String> {
+    serde_json::to_string_pretty(&upcoming(schedules)).map_err(|e| format!("Failed To Serialize: {e:?}"))
+}
+
+// Content lines are at most 75 octets, longer ones carry on in lines starting with a space
+fn fold(line: &str) -> String {
+    let mut ret = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            ret += "\r\n ";
+            length = 1;
+        }
+        ret.push(c);
+        length += c.len_utf8();
+    }
+    ret
+}
+
+pub fn to_ics(schedules: &[Box<dyn Sche>]) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace(',', "\\,").replace(';', "\\;").replace("\r\n", "\\n").replace('\n', "\\n");
+    let time = |t: i64| chrono::DateTime::from_timestamp(t, 0).map(|t| t.format("%Y%m%dT%H%M%SZ").to_string()).unwrap_or_default();
+    let mut ret = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Splatink//Splatoon 3 Rotation Display//EN".to_string(),
+    ];
+    let stamp = time(chrono::Utc::now().timestamp());
+    for event in upcoming(schedules) {
+        ret.push("BEGIN:VEVENT".to_string());
+        ret.push(format!("UID:{}-{}@splatink", event.schedule, event.start));
+        ret.push(format!("DTSTAMP:{stamp}"));
+        ret.push(format!("DTSTART:{}", time(event.start)));
+        ret.push(format!("DTEND:{}", time(event.end)));
+        ret.push(format!("SUMMARY:{}", escape(&format!("{}: {}", event.title, event.summary))));
+        ret.push("END:VEVENT".to_string());
+    }
+    ret.push("END:VCALENDAR".to_string());
+    ret.iter().map(|l| fold(l)).collect::<Vec<_>>().join("\r\n") + "\r\n"
+}
@@ -0,0 +1,71 @@
+use crate::rm_structure::{VsSetting, Weapon};
+
+// Rotations worth pointing out, from the FavoriteRules=, FavoriteStages= and
+// Favorites= options of the Core measure. Stages can be given by name or by id
+#[derive(Clone, Default)]
+pub struct Favorites {
+    pub rules: Vec<String>,
+    pub stages: Vec<String>,
+    // A rule on a stage, written as `Splat Zones:Mahi-Mahi Resort`
+    pub combos: Vec<(String, String)>,
+}
+
+// Salmon Run weapons from the PreferredWeapons= and AvoidedWeapons= options. Rotations
+// score a point for every preferred weapon and lose one for every avoided one
+#[derive(Clone, Default)]
+pub struct WeaponPreferences {
+    pub preferred: Vec<String>,
+    pub avoided: Vec<String>,
+}
+
+fn split_list(list: &str) -> impl Iterator<Item = String> + '_ {
+    list.split('|').map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+impl Favorites {
+    pub fn parse(rules: &str, stages: &str, combos: &str) -> Result<Favorites, String> {
+        Ok(Favorites {
+            rules: split_list(rules).collect(),
+            stages: split_list(stages).collect(),
+            combos: split_list(combos)
+                .map(|combo| match combo.split_once(':') {
+                    Some((rule, stage)) => Ok((rule.trim().to_string(), stage.trim().to_string())),
+                    None => Err(format!("Invalid Favorite: {combo}, Expected Rule:Stage")),
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.stages.is_empty() && self.combos.is_empty()
+    }
+    // A rotation is a favorite when its rule or one of its stages is, or when a combo
+    // matches both
+    pub fn matches(&self, setting: &VsSetting) -> bool {
+        let rule = |r: &String| r.eq_ignore_ascii_case(&setting.vs_rule.name);
+        let stage = |s: &String| [&setting.vs_stages.0, &setting.vs_stages.1].iter()
+            .any(|stage| s.eq_ignore_ascii_case(&stage.name) || *s == stage.id);
+        self.rules.iter().any(rule)
+            || self.stages.iter().any(stage)
+            || self.combos.iter().any(|(r, s)| rule(r) && stage(s))
+    }
+}
+
+impl WeaponPreferences {
+    pub fn parse(preferred: &str, avoided: &str) -> WeaponPreferences {
+        WeaponPreferences {
+            preferred: split_list(preferred).collect(),
+            avoided: split_list(avoided).collect(),
+        }
+    }
+    pub fn is_preferred(&self, name: &str) -> bool {
+        self.preferred.iter().any(|w| w.eq_ignore_ascii_case(name))
+    }
+    pub fn is_avoided(&self, name: &str) -> bool {
+        self.avoided.iter().any(|w| w.eq_ignore_ascii_case(name))
+    }
+    pub fn score(&self, weapons: &[Weapon]) -> i32 {
+        weapons.iter()
+            .map(|w| self.is_preferred(&w.name) as i32 - self.is_avoided(&w.name) as i32)
+            .sum()
+    }
+}
@@ -0,0 +1,113 @@
+extern crate chrono;
+extern crate serde;
+use std::collections::BTreeMap;
+use self::chrono::{DateTime, Duration, Local};
+use self::serde::{Deserialize, Serialize};
+use crate::{alerts::AlertRule, rm_structure::{Query, Sche}};
+
+pub const HISTORY_FILE_NAME: &str = "History.json";
+pub const HISTORY_EXPORT_FILE_NAME: &str = "Splatoon 3 History.json";
+// Query fields saved with every rotation, whichever ones the schedule has
+const FIELDS: [&str; 13] = ["title", "rule", "stage1", "stage2", "stage", "king", "kind", "bigrun", "random", "weapon1", "weapon2", "weapon3", "weapon4"];
+// A bit over a year, so the same month last year can still be looked at
+const HISTORY_DAYS: i64 = 400;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub schedule: String,
+    pub start: i64,
+    pub end: i64,
+    pub summary: String,
+    pub fields: BTreeMap<String, String>,
+}
+impl Query for HistoryEntry {
+    fn run_time(&self) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        let time = |t: i64| DateTime::from_timestamp(t, 0).map(|t| t.with_timezone(&Local));
+        Some((time(self.start)?, time(self.end)?))
+    }
+    fn query(&self, field: &str) -> Option<String> {
+        self.fields.get(&field.to_lowercase()).cloned()
+    }
+    fn summary(&self) -> String {
+        self.summary.clone()
+    }
+}
+
+// Every rotation seen in the schedule data, kept in HISTORY_FILE_NAME so they're
+// still around after they drop out of the feed
+#[derive(Serialize, Deserialize, Default)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    // A missing file is just an empty history
+    pub fn load(path: &str) -> Result<History, String> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| format!("Failed To Parse History: {e:?}")),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(History::default()),
+            Err(e) => Err(format!("Failed To Read History: {e:?}")),
+        }
+    }
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| format!("Failed To Serialize History: {e:?}"))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed To Write History: {e:?}"))
+    }
+    // Adds the rotations that aren't in yet. Ones that haven't started are replaced,
+    // since the feed can still change them. Returns whether anything changed
+    pub fn record(&mut self, schedules: &[Box<dyn Sche>]) -> bool {
+        let now = Local::now().timestamp();
+        let mut changed = false;
+        for sche in schedules {
+            for event in (0..).map_while(|i| sche.event(i)) {
+                let Some((start, end)) = event.run_time() else { continue };
+                let entry = HistoryEntry {
+                    schedule: sche.get_id().to_string(),
+                    start: start.timestamp(),
+                    end: end.timestamp(),
+                    summary: event.summary(),
+                    fields: FIELDS.iter().filter_map(|f| Some((f.to_string(), event.query(f)?))).collect(),
+                };
+                match self.entries.iter_mut().find(|e| e.schedule == entry.schedule && e.start == entry.start) {
+                    Some(existing) if existing.start > now && (existing.summary != entry.summary || existing.end != entry.end) => {
+                        *existing = entry;
+                        changed = true;
+                    },
+                    Some(_) => {},
+                    None => {
+                        self.entries.push(entry);
+                        changed = true;
+                    },
+                }
+            }
+        }
+        if changed {
+            self.entries.sort_by_key(|e| e.start);
+        }
+        changed
+    }
+    pub fn prune(&mut self) {
+        let cutoff = (Local::now() - Duration::days(HISTORY_DAYS)).timestamp();
+        self.entries.retain(|e| e.end > cutoff);
+    }
+    // Rotations of a schedule that are over, the most recent first
+    pub fn previous(&self, schedule: &str, count: usize) -> Vec<HistoryEntry> {
+        let now = Local::now().timestamp();
+        self.entries.iter().rev()
+            .filter(|e| e.schedule.eq_ignore_ascii_case(schedule) && e.end <= now)
+            .take(count)
+            .cloned()
+            .collect()
+    }
+    // Rotations that started in the last `days` days and match the filter, which is
+    // written like an `Alert=` option, e.g. `Rule:Clam Blitz; Stage:Mahi-Mahi Resort`
+    pub fn matching(&self, days: i64, filter: &AlertRule) -> Vec<&HistoryEntry> {
+        let since = (Local::now() - Duration::days(days)).timestamp();
+        self.entries.iter()
+            .filter(|e| e.start >= since && filter.matches(&e.schedule, *e))
+            .collect()
+    }
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.entries).map_err(|e| format!("Failed To Serialize: {e:?}"))
+    }
+}
@@ -2,78 +2,169 @@ mod schedule_data;
 mod splatfest_data;
 mod rm_structure;
 mod rm_write;
+mod rm_layout;
+mod theme;
+mod style;
 mod measure;
+mod alerts;
+mod command;
+mod export;
+mod favorites;
+mod locale;
+mod store;
+mod timebar;
+mod markup;
+mod history;
+mod stats;
 mod rainmeter;
 mod github_data;
 
 use measure::Measure;
-use rainmeter::api::RmApi;
+use timebar::TimeBar;
+use rainmeter::api::{LogType, RmApi};
 use rainmeter::types::*;
 
+// A panic unwinding into Rainmeter crashes it, so every export runs through here.
+// Panics are logged and the export returns `default` instead
+fn guard<T>(name: &str, data: Option<RmData>, default: T, f: impl FnOnce() -> T) -> T {
+  // Initialize may have failed and left no measure behind
+  if data.is_some_and(|d| d.is_null()) {
+    return default;
+  }
+  match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+    Ok(ret) => ret,
+    Err(e) => {
+      let message = e.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| e.downcast_ref::<String>().cloned())
+        .unwrap_or("Unknown Panic".to_string());
+      RmApi::ls_log(LogType::Error, format!("Splatink: {name} Panicked: {message}"));
+      default
+    }
+  }
+}
+
+// Type= picks what a measure is when it's created. Only Core measures pull data,
+// the others are kept as small as possible since skins have lots of them
+enum Instance {
+  Core(Box<Measure>),
+  TimeBar(TimeBar),
+}
+
 #[export_name = "Initialize"]
 fn initialize(data: &mut RmData, rm: RmRm) {
-  let measure = Measure::new(RmApi::new(rm));
-  rm_set_data!(data, measure);
+  *data = std::ptr::null_mut();
+  guard("Initialize", None, (), || {
+    let api = RmApi::new(rm);
+    let instance = match api.read_string("Type", "Core", None).to_lowercase().as_str() {
+      "timebar" => Instance::TimeBar(TimeBar::new(api)),
+      _ => Instance::Core(Box::new(Measure::new(api))),
+    };
+    rm_set_data!(data, instance);
+  })
 }
 
 #[export_name = "Finalize"]
 fn finalize(data: RmData) {
-  let measure = rm_borrow_data!(data, Measure);
-  measure.dispose();
+  guard("Finalize", Some(data), (), || {
+    if let Instance::Core(measure) = rm_borrow_data!(data, Instance) {
+      measure.dispose();
+    }
 
-  rm_drop_data!(data, Measure);
+    rm_drop_data!(data, Instance);
+  })
 }
 
 #[export_name = "Reload"]
 fn reload(data: RmData, rm: RmRm, max_value: &mut f64) {
-  let measure = rm_borrow_data!(data, Measure);
-  measure.reload(RmApi::new(rm), max_value);
+  guard("Reload", Some(data), (), || {
+    match rm_borrow_data!(data, Instance) {
+      Instance::Core(measure) => measure.reload(RmApi::new(rm), max_value),
+      Instance::TimeBar(time_bar) => time_bar.reload(RmApi::new(rm)),
+    }
+  })
 }
 
 #[export_name = "Update"]
 fn update(data: RmData) -> f64 {
-  let measure = rm_borrow_data!(data, Measure);
-  measure.update()
+  guard("Update", Some(data), 0.0, || {
+    match rm_borrow_data!(data, Instance) {
+      Instance::Core(measure) => measure.update(),
+      Instance::TimeBar(time_bar) => time_bar.update(),
+    }
+  })
 }
 
 #[export_name = "GetString"]
 fn get_string(data: RmData) -> RmString {
-  let measure = rm_borrow_data!(data, Measure);
-
-  if let Some(string) = measure.get_string() {
-    return rm_to_string!(string);
-  }
+  guard("GetString", Some(data), rm_null_string!(), || {
+    match rm_borrow_data!(data, Instance) {
+      Instance::Core(measure) => {
+        if let Some(string) = measure.get_string() {
+          return rm_to_string!(measure.string_buffer, string);
+        }
+      },
+      Instance::TimeBar(time_bar) => {
+        if let Some(string) = time_bar.get_string() {
+          return rm_to_string!(time_bar.string_buffer, string);
+        }
+      },
+    }
 
-  rm_null_string!()
+    rm_null_string!()
+  })
 }
 
 #[export_name = "ExecuteBang"]
 fn execute_bang(data: RmData, args: RmString) {
-  let measure = rm_borrow_data!(data, Measure);
-  measure.execute_bang(rm_parse_string!(args));
+  guard("ExecuteBang", Some(data), (), || {
+    if let Instance::Core(measure) = rm_borrow_data!(data, Instance) {
+      measure.execute_bang(rm_parse_string!(args));
+    }
+  })
 }
 
-// Example of a custom function
-#[export_name = "ToRandomCase"]
-fn to_random_case(data: RmData, argc: i32, argv: RmArgv) -> RmString {
-  let _ = rm_borrow_data!(data, Measure);
-  let args = rm_parse_args!(&argv, argc);
-
-  if args.len() == 1 {
-    // This isn't actually random to not require the rand crate,
-    // so I just alternative upper-lower for this example.
-    let mut result = String::new();
-    let mut uppercase = false;
-    for c in args[0].chars() {
-      if uppercase {
-        result.push(c.to_ascii_uppercase());
-      } else {
-        result.push(c.to_ascii_lowercase());
-      }
-      uppercase = !uppercase;
+fn section_function(data: RmData, argc: i32, argv: RmArgv, name: &str) -> RmString {
+  guard(name, Some(data), rm_null_string!(), || {
+    let Instance::Core(measure) = rm_borrow_data!(data, Instance) else {
+      return rm_null_string!();
+    };
+    // FestState() is called without arguments and argv may be null then
+    let args = if argc > 0 {rm_parse_args!(&argv, argc)} else {Vec::new()};
+
+    if let Some(string) = measure.section_function(name, &args) {
+      return rm_to_string!(measure.string_buffer, string);
     }
-    return rm_to_string!(result);
-  }
 
-  rm_null_string!()
+    rm_null_string!()
+  })
+}
+
+#[export_name = "Current"]
+fn current(data: RmData, argc: i32, argv: RmArgv) -> RmString {
+  section_function(data, argc, argv, "current")
+}
+
+#[export_name = "Next"]
+fn next(data: RmData, argc: i32, argv: RmArgv) -> RmString {
+  section_function(data, argc, argv, "next")
+}
+
+#[export_name = "TimeLeft"]
+fn time_left(data: RmData, argc: i32, argv: RmArgv) -> RmString {
+  section_function(data, argc, argv, "timeleft")
+}
+
+#[export_name = "Previous"]
+fn previous(data: RmData, argc: i32, argv: RmArgv) -> RmString {
+  section_function(data, argc, argv, "previous")
+}
+
+#[export_name = "HistoryCount"]
+fn history_count(data: RmData, argc: i32, argv: RmArgv) -> RmString {
+  section_function(data, argc, argv, "historycount")
+}
+
+#[export_name = "FestState"]
+fn fest_state(data: RmData, argc: i32, argv: RmArgv) -> RmString {
+  section_function(data, argc, argv, "feststate")
 }
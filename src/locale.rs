@@ -0,0 +1,98 @@
+extern crate serde;
+use std::collections::HashMap;
+use self::serde::Deserialize;
+use crate::schedule_data::RotationData;
+
+pub const DEFAULT_LANGUAGE: &str = "en-US";
+
+#[derive(Deserialize, Default)]
+pub struct Locale {
+    #[serde(default)]
+    stages: HashMap<String, Named>,
+    #[serde(default)]
+    weapons: HashMap<String, Named>,
+    #[serde(default)]
+    events: HashMap<String, EventNames>,
+}
+
+#[derive(Deserialize)]
+struct Named {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct EventNames {
+    name: String,
+    desc: String,
+    regulation: String,
+}
+
+impl Locale {
+    pub fn url(language: &str) -> String {
+        format!("https://splatoon3.ink/data/locale/{language}.json")
+    }
+    pub fn file_name(language: &str) -> String {
+        format!("Locale {language}.json")
+    }
+
+    // Rule names are left alone since the bundled mode icons are named after them
+    pub fn apply(&self, data: &RotationData) -> RotationData {
+        let mut ret = data.clone();
+        let stage = |id: &str, name: &mut String| {
+            if let Some(n) = self.stages.get(id) {
+                *name = n.name.clone();
+            }
+        };
+        let d = &mut ret.data;
+        for ele in d.regularSchedules.nodes.iter_mut() {
+            if let Some(s) = &mut ele.regularMatchSetting {
+                stage(&s.vsStages.0.id, &mut s.vsStages.0.name);
+                stage(&s.vsStages.1.id, &mut s.vsStages.1.name);
+            }
+        }
+        for ele in d.bankaraSchedules.nodes.iter_mut() {
+            if let Some((a, b)) = &mut ele.bankaraMatchSettings {
+                for s in [a, b] {
+                    stage(&s.vsStages.0.id, &mut s.vsStages.0.name);
+                    stage(&s.vsStages.1.id, &mut s.vsStages.1.name);
+                }
+            }
+        }
+        for ele in d.xSchedules.nodes.iter_mut() {
+            if let Some(s) = &mut ele.xMatchSetting {
+                stage(&s.vsStages.0.id, &mut s.vsStages.0.name);
+                stage(&s.vsStages.1.id, &mut s.vsStages.1.name);
+            }
+        }
+        for ele in d.festSchedules.nodes.iter_mut() {
+            if let Some((a, b)) = &mut ele.festMatchSettings {
+                for s in [a, b] {
+                    stage(&s.vsStages.0.id, &mut s.vsStages.0.name);
+                    stage(&s.vsStages.1.id, &mut s.vsStages.1.name);
+                }
+            }
+        }
+        for ele in d.eventSchedules.nodes.iter_mut() {
+            let s = &mut ele.leagueMatchSetting;
+            stage(&s.vsStages.0.id, &mut s.vsStages.0.name);
+            stage(&s.vsStages.1.id, &mut s.vsStages.1.name);
+            if let Some(e) = self.events.get(&s.leagueMatchEvent.id) {
+                s.leagueMatchEvent.name = e.name.clone();
+                s.leagueMatchEvent.desc = e.desc.clone();
+                s.leagueMatchEvent.regulation = e.regulation.clone();
+            }
+        }
+        let coop = &mut d.coopGroupingSchedule;
+        for setting in coop.regularSchedules.nodes.iter_mut().map(|e| &mut e.setting)
+            .chain(coop.bigRunSchedules.nodes.iter_mut().map(|e| &mut e.setting))
+            .chain(coop.teamContestSchedules.nodes.iter_mut().map(|e| &mut e.setting)) {
+            stage(&setting.coopStage.id, &mut setting.coopStage.name);
+            for weapon in setting.weapons.iter_mut() {
+                if let Some(n) = self.weapons.get(&weapon.__splatoon3ink_id) {
+                    weapon.name = n.name.clone();
+                }
+            }
+        }
+        ret
+    }
+}
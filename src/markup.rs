@@ -0,0 +1,47 @@
+// League event descriptions and regulations come with a little HTML mixed in, `<br />`
+// for line breaks, `・` for list items and the odd entity. This turns them into lines
+// of plain text
+pub fn to_lines(markup: &str) -> Vec<String> {
+    let text = markup.replace("<br />", "\n").replace("<br/>", "\n").replace("<br>", "\n");
+    let mut stripped = String::new();
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+    let decoded = stripped
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    let mut ret: Vec<String> = Vec::new();
+    for line in decoded.lines().map(|l| l.trim()) {
+        // Runs of blank lines collapse into one, and none are kept at the start
+        if line.is_empty() && ret.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        ret.push(match line.strip_prefix('・') {
+            Some(item) => format!("• {}", item.trim()),
+            None => line.to_string(),
+        });
+    }
+    while ret.last().is_some_and(|l| l.is_empty()) {
+        ret.pop();
+    }
+    ret
+}
+
+pub fn to_text(markup: &str) -> String {
+    to_lines(markup).join("\n")
+}
+
+// Options are single lines, Rainmeter puts #CRLF# back as a line break in text and tooltips
+pub fn to_skin_text(text: &str) -> String {
+    text.replace('\n', "#CRLF#")
+}
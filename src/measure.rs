@@ -1,23 +1,50 @@
 extern crate chrono;
 extern crate serde;
 extern crate reqwest;
-use self::chrono::Duration;
 use self::serde::Deserialize;
-use self::reqwest::blocking::{ClientBuilder, Client};
-use rainmeter::api::RmApi;
-use crate::{github_data::Releases, rm_structure::{Download, RmStructure}, rm_write::{write_to_skin, MeasureOptions, MeasureType, ObjectType, PluginType, RmObject, SplatinkType, TimeBarOptions, ToRM}, schedule_data::RotationData, splatfest_data::SplatfestData};
+use self::reqwest::blocking::Client;
+use std::{rc::Rc, sync::Arc};
+use rainmeter::{api::RmApi, types::wchar_t};
+use crate::{alerts::{AlertRule, Alerts, ALERTS_FILE_NAME}, command::{CacheKind, Command, ExportFormat, LANGUAGES, REGIONS}, export::{to_ics, to_json, ICS_FILE_NAME, JSON_FILE_NAME as EXPORT_JSON_FILE_NAME}, favorites::{Favorites, WeaponPreferences}, history::{History, HISTORY_EXPORT_FILE_NAME, HISTORY_FILE_NAME}, stats::{Stats, STATS_FILE_NAME}, locale::{Locale, DEFAULT_LANGUAGE}, github_data::Releases, rm_structure::{format_duration, Download, Query, RmStructure, Sche}, rm_write::{changed_variables, diff_skins, is_single_file_skin, merge_skin, read_skin, skin_header, validate_skin, write_to_skin, GENERATED_FILE_NAME, MeasureOptions, MeasureType, ObjectType, PluginType, RmObject, SplatinkType, ToRM}, schedule_data::RotationData, splatfest_data::SplatfestData, store, theme::{Theme, THEME_FILE_NAME}, style::{CoopFilter, LayoutMode, Orientation, OutputMode, Style}};
+
+type Schedules = Rc<Vec<Box<dyn Sche>>>;
 
 #[allow(non_snake_case)]
 pub struct Measure {
     pub rm_api: RmApi,
-    pub measure_type: SplatinkType,
+    sche: String,
     prev_sche: String,
-    schedules: Option<RotationData>,
+    // This instance's view of the shared store, swapped when `data_version` falls behind
+    schedules: Option<Arc<RotationData>>,
+    data_version: u64,
+    // `schedules` generated in a language, for the data version and language they were made from
+    generated: Option<(u64, String, Schedules)>,
     pub RESOURCE_DIR: String,
     pub SKIN_PATH: String,
-    web_pull_cooldown: Duration,
-    web_pull_cooldown_set: u32,
-    web_client: Client,
+    measure_name: String,
+    generated_file: String,
+    theme_preset: String,
+    scale: f64,
+    layout: LayoutMode,
+    orientation: Orientation,
+    columns: Vec<String>,
+    coop_filter: CoopFilter,
+    favorites: Favorites,
+    weapons: WeaponPreferences,
+    alerts: Alerts,
+    alert_action: String,
+    alert_sound: String,
+    // Alerts are looked for once a minute, this is the last minute that was checked
+    alert_minute: i64,
+    history: History,
+    previous_rotations: usize,
+    show_stats: bool,
+    stats_days: i64,
+    output: OutputMode,
+    region: String,
+    language: String,
+    locale: Locale,
+    pub string_buffer: Vec<wchar_t>,
 }
 
 const SCHEDULE_URL: &str = "https://splatoon3.ink/data/schedules.json";
@@ -25,6 +52,8 @@ const SPLATFEST_URL: &str = "https://splatoon3.ink/data/festivals.json";
 const GITHUB_RELEASES_URL: &str = "https://api.github.com/repos/LightspeedLazer/Splatoon-3-Rotation-Display/releases";
 
 const SCHEDULE_JSON_NAME: &str = "Schedules Json.json";
+const CUSTOMIZATIONS_FILE_NAME: &str = "Customizations.inc";
+const DOWNLOADED_IMAGE_DIRS: [&str; 3] = ["Stages", "Weapons", "Splatfest Teams"];
 const JSON_FILE_DIR: &str = "";
 const SCHEDULE_JSON_SOURCE: JsonSource = JsonSource::Web;
 // const SCHEDULE_JSON_SOURCE: JsonSource = JsonSource::Storage("Drizzle Tricolor 9_10");
@@ -41,104 +70,402 @@ enum JsonSource<'a> {
 impl Measure {
     pub fn new(api: RmApi) -> Measure {
         let RESOURCE_DIR = api.read_path("DONTNAMESOMETHINGTHIS", "@Resources");
-        let SKIN_PATH = api.read_path("DONTNAMESOMETHINGTHIS", "Splatoon3RotationDisplay.ini");
+        // Each Core measure generates the skin it's in unless pointed somewhere else,
+        // so several skins can run side by side
+        let SKIN_PATH = api.read_path("SkinFile", &api.replace_variables("#CURRENTFILE#"));
+        let measure_name = api.get_measure_name();
+        let generated_file = api.read_string("GeneratedFile", GENERATED_FILE_NAME, None);
+        let fired = Alerts::read_fired(&format!("{RESOURCE_DIR}/{ALERTS_FILE_NAME}"));
+        let history = History::load(&format!("{RESOURCE_DIR}/{HISTORY_FILE_NAME}")).unwrap_or_else(|e| {
+            api.log(crate::rainmeter::api::LogType::Warning, e);
+            History::default()
+        });
+        store::acquire();
         Measure {
             rm_api: api,
-            measure_type: SplatinkType::Core("RegSche".to_string()),
+            sche: "RegSche".to_string(),
             prev_sche: "RegSche".to_string(),
             schedules: None,
+            data_version: 0,
+            generated: None,
             RESOURCE_DIR,
             SKIN_PATH,
-            web_pull_cooldown: Duration::seconds(0),
-            web_pull_cooldown_set: 2,
-            web_client: ClientBuilder::new().user_agent("Splatoon-3-Rotation-Display").build().unwrap(),
+            measure_name,
+            generated_file,
+            theme_preset: "Dark".to_string(),
+            scale: 1.0,
+            layout: LayoutMode::Standard,
+            orientation: Orientation::Vertical,
+            columns: Vec::new(),
+            coop_filter: CoopFilter::All,
+            favorites: Favorites::default(),
+            weapons: WeaponPreferences::default(),
+            alerts: Alerts {
+                rules: Vec::new(),
+                lead_time: chrono::Duration::minutes(10),
+                fired,
+            },
+            alert_action: String::new(),
+            alert_sound: String::new(),
+            alert_minute: 0,
+            history,
+            previous_rotations: 0,
+            show_stats: false,
+            stats_days: 30,
+            output: OutputMode::Literal,
+            region: REGIONS[0].to_string(),
+            language: DEFAULT_LANGUAGE.to_string(),
+            locale: Locale::default(),
+            string_buffer: Vec::new(),
         }
     }
-    pub fn dispose(&self) {}
+    pub fn dispose(&self) {
+        store::release();
+    }
     #[allow(unused)]
     pub fn reload(&mut self, rm_api: RmApi, max_value: &mut f64) {
         self.rm_api = rm_api;
-        let type_string = self.rm_api.read_string("Type", "Core", None);
-        let selected_sche = self.rm_api.read_string("Sche", "RegSche", None);
-        let start_time = self.rm_api.read_int("StartTime", 0) as i64;
-        let end_time = self.rm_api.read_int("EndTime", 0) as i64;
-
-        self.measure_type = if type_string == "TimeBar" {
-            SplatinkType::TimeBar(TimeBarOptions{
-                start_time,
-                end_time
-            })
-        } else {
-            SplatinkType::Core(selected_sche.clone())
-        };  
-
-        if let SplatinkType::Core(_) = self.measure_type {
-            self.check();
+        self.sche = self.rm_api.read_string("Sche", "RegSche", None);
+        self.theme_preset = self.rm_api.read_string("Theme", "Dark", None);
+        self.scale = self.rm_api.read_double("Scale", 1.0).max(0.1);
+        let layout_string = self.rm_api.read_string("Layout", "Standard", None);
+        self.layout = LayoutMode::parse(&layout_string).unwrap_or_else(|| {
+            self.rm_api.log(crate::rainmeter::api::LogType::Warning, format!("Unknown Layout: {layout_string}"));
+            LayoutMode::Standard
+        });
+        let orientation_string = self.rm_api.read_string("Orientation", "Vertical", None);
+        self.orientation = Orientation::parse(&orientation_string).unwrap_or_else(|| {
+            self.rm_api.log(crate::rainmeter::api::LogType::Warning, format!("Unknown Orientation: {orientation_string}"));
+            Orientation::Vertical
+        });
+        let output_string = self.rm_api.read_string("Output", "Literal", None);
+        self.output = OutputMode::parse(&output_string).unwrap_or_else(|| {
+            self.rm_api.log(crate::rainmeter::api::LogType::Warning, format!("Unknown Output: {output_string}"));
+            OutputMode::Literal
+        });
+        self.region = self.rm_api.read_string("Region", REGIONS[0], None).to_uppercase();
+        if !REGIONS.contains(&self.region.as_str()) {
+            self.rm_api.log(crate::rainmeter::api::LogType::Warning, format!("Unknown Region: {}", self.region));
         }
+        let language = self.rm_api.read_string("Language", DEFAULT_LANGUAGE, None);
+        if language != self.language {
+            self.language = language;
+            self.locale = self.load_locale();
+        }
+        self.favorites = Favorites::parse(
+            &self.rm_api.read_string("FavoriteRules", "", None),
+            &self.rm_api.read_string("FavoriteStages", "", None),
+            &self.rm_api.read_string("Favorites", "", None),
+        ).unwrap_or_else(|e| {
+            self.rm_api.log(crate::rainmeter::api::LogType::Warning, e);
+            Favorites::default()
+        });
+        let coop_filter_string = self.rm_api.read_string("CoopFilter", "All", None);
+        self.coop_filter = CoopFilter::parse(&coop_filter_string).unwrap_or_else(|| {
+            self.rm_api.log(crate::rainmeter::api::LogType::Warning, format!("Unknown CoopFilter: {coop_filter_string}"));
+            CoopFilter::All
+        });
+        self.weapons = WeaponPreferences::parse(
+            &self.rm_api.read_string("PreferredWeapons", "", None),
+            &self.rm_api.read_string("AvoidedWeapons", "", None),
+        );
+        // Alert=, Alert2=, Alert3= and so on, stopping at the first one that's missing
+        self.alerts.rules = Vec::new();
+        for i in 1.. {
+            let option = if i == 1 {"Alert".to_string()} else {format!("Alert{i}")};
+            let rule = self.rm_api.read_string(&option, "", None);
+            if rule.is_empty() {
+                break;
+            }
+            match rule.parse::<AlertRule>() {
+                Ok(rule) => self.alerts.rules.push(rule),
+                Err(e) => {self.rm_api.log(crate::rainmeter::api::LogType::Warning, format!("{option}: {e}"));},
+            }
+        }
+        self.alerts.lead_time = chrono::Duration::minutes(self.rm_api.read_int("AlertLeadTime", 10).max(0) as i64);
+        self.alert_action = self.rm_api.read_string("OnFavoriteAction", "", Some(false));
+        self.alert_sound = self.rm_api.read_string("AlertSound", "", None);
+        self.previous_rotations = self.rm_api.read_int("PreviousRotations", 0).max(0) as usize;
+        self.show_stats = self.rm_api.read_int("ShowStats", 0) != 0;
+        self.stats_days = self.rm_api.read_int("StatsDays", 30).max(1) as i64;
+        self.columns = self.rm_api.read_string("Columns", "", None).split('|').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+        self.check();
     }
     pub fn execute_bang(&mut self, args: String) {
-        let iter: Vec<&str> = args.split_whitespace().collect();
-
-        if let SplatinkType::Core(_) = self.measure_type {
-            #[allow(clippy::single_match)]
-            match iter[0].to_lowercase().as_str() {
-                "refreshfile" => {
-                    let _ = self.rewrite_file().map_err(|e| self.rm_api.log(crate::rainmeter::api::LogType::Error, e));
-                },
-                "repulldata" => {
-                    let _ = std::fs::remove_file(format!("{}/{SCHEDULE_JSON_NAME}", self.RESOURCE_DIR));
-                    self.rm_api.execute_self("!CommandMeasure SplatinkCore refreshfile");
-                },
-                "redrawsche" => {
-                    self.measure_type = SplatinkType::Core(iter[1].to_string());
-                    self.rm_api.execute_self("!UpdateMeasure SplatinkCore");
-                },
-                _ => {},
-            }
+        let result = args.parse::<Command>().and_then(|command| self.run_command(command));
+        if let Err(e) = result {
+            self.rm_api.log(crate::rainmeter::api::LogType::Error, format!("{e} ({})", args.trim()));
         }
     }
-    pub fn update(&mut self) -> f64 {
-        match self.measure_type.clone() {
-            SplatinkType::Core(sche) => {
-                if SCHEDULE_JSON_SOURCE == JsonSource::Web {
-                    self.check();
+    fn run_command(&mut self, command: Command) -> Result<(), String> {
+        match command {
+            Command::RefreshFile => self.rewrite_file(),
+            Command::RepullData => {
+                store::with(|s| s.request_repull());
+                self.check();
+                self.rewrite_file()
+            },
+            Command::RedrawSche(id) => self.select_sche(&id),
+            Command::Next | Command::Prev => {
+                let ids = self.schedule_ids();
+                let step = if let Command::Next = command {1} else {-1};
+                let current = ids.iter().position(|id| *id == self.sche).unwrap_or(0) as isize;
+                match ids.get((current + step).rem_euclid(ids.len().max(1) as isize) as usize) {
+                    Some(id) => self.select_sche(&id.clone()),
+                    None => Err("No Schedules".to_string()),
                 }
-                if sche != self.prev_sche {
-                    self.rm_api.execute_self(&format!("!HideMeterGroup {}", self.prev_sche));
-                    self.rm_api.execute_self(&format!("!ShowMeterGroup {}", sche));
-                    self.rm_api.execute_self("!Redraw");
-                    self.prev_sche = sche.to_string();
+            },
+            Command::Goto(index) => {
+                let ids = self.schedule_ids();
+                let id = ids.get(index - 1).ok_or(format!("Only {} Schedules", ids.len()))?;
+                self.select_sche(&id.clone())
+            },
+            Command::Pin(id) => {
+                // Pinning writes the schedule into the main skin so it's picked after every refresh
+                let value = match &id {
+                    Some(id) => {
+                        self.select_sche(id)?;
+                        id.clone()
+                    },
+                    None => "#CurrentSche#".to_string(),
+                };
+                self.rm_api.execute_self(&format!("!WriteKeyValue {} Sche \"{value}\" \"{}\"", self.measure_name, self.SKIN_PATH));
+                Ok(())
+            },
+            Command::Export(format) => {
+                let schedules = self.generated_schedules().ok_or("No Schedule To Export".to_string())?;
+                let (name, contents) = match format {
+                    ExportFormat::Ics => (ICS_FILE_NAME, to_ics(&schedules)),
+                    ExportFormat::Json => (EXPORT_JSON_FILE_NAME, to_json(&schedules)?),
+                    ExportFormat::History => (HISTORY_EXPORT_FILE_NAME, self.history.to_json()?),
+                    ExportFormat::Stats => (STATS_FILE_NAME, Stats::compute(&self.history, self.stats_days).to_json()?),
+                };
+                let path = format!("{}/{name}", self.RESOURCE_DIR);
+                std::fs::write(&path, contents).map_err(|e| format!("Failed To Write Export: {e:?}"))?;
+                self.rm_api.log(crate::rainmeter::api::LogType::Notice, format!("Exported to {path}"));
+                Ok(())
+            },
+            Command::ClearCache(CacheKind::Images) => {
+                for dir in DOWNLOADED_IMAGE_DIRS {
+                    let entries = std::fs::read_dir(format!("{}/{dir}", self.RESOURCE_DIR)).into_iter().flatten().flatten();
+                    for path in entries.map(|e| e.path()).filter(|p| p.extension().is_some_and(|e| e == "png")) {
+                        std::fs::remove_file(&path).map_err(|e| format!("Failed To Remove {}: {e:?}", path.display()))?;
+                    }
+                }
+                self.rewrite_file()?;
+                self.rm_api.execute_self("!Refresh");
+                Ok(())
+            },
+            Command::ClearCache(CacheKind::Json) => {
+                let _ = std::fs::remove_file(format!("{}/{SCHEDULE_JSON_NAME}", self.RESOURCE_DIR));
+                for language in LANGUAGES {
+                    let _ = std::fs::remove_file(format!("{}/{}", self.RESOURCE_DIR, Locale::file_name(language)));
+                }
+                store::with(|s| s.request_repull());
+                self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Cleared cached json");
+                Ok(())
+            },
+            Command::Status => {
+                let ids = self.schedule_ids();
+                self.rm_api.log(crate::rainmeter::api::LogType::Notice, format!("Schedule: {}, Schedules: {}", self.sche, ids.join(", ")));
+                self.rm_api.log(crate::rainmeter::api::LogType::Notice, format!("Region: {}, Language: {}, Output: {}", self.region, self.language, self.output.name()));
+                self.rm_api.log(crate::rainmeter::api::LogType::Notice, match self.schedules.as_ref().and_then(|s| s.data.regularSchedules.nodes.last()) {
+                    Some(last) => format!("Schedule data until {}", last.endTime.format("%-m/%-d %-I%P")),
+                    None => "No schedule data".to_string(),
+                });
+                let cooldown = store::with(|s| s.web_pull_cooldown());
+                if !cooldown.is_zero() {
+                    self.rm_api.log(crate::rainmeter::api::LogType::Notice, format!("Web requesting on cooldown for {:02}:{:02}", cooldown.num_minutes(), cooldown.num_seconds() % 60));
+                }
+                Ok(())
+            },
+            Command::History(days, filter) => {
+                let found = self.history.matching(days, &filter);
+                self.rm_api.log(crate::rainmeter::api::LogType::Notice, format!("{} matching rotations in the last {days} days", found.len()));
+                for entry in found.iter() {
+                    let start = entry.run_time().map(|(start, _)| start.format("%-m/%-d %-I%P").to_string()).unwrap_or_default();
+                    self.rm_api.log(crate::rainmeter::api::LogType::Notice, format!("{start} {}: {}", entry.schedule, entry.summary));
                 }
+                // Kept as a variable so meters can show the last count
+                self.rm_api.execute_self(&format!("!SetVariable HistoryCount {}", found.len()));
+                Ok(())
             },
-            SplatinkType::TimeBar(ref o) => {
-                return (chrono::Local::now().timestamp() - o.start_time).max(0) as f64 / (o.end_time - o.start_time) as f64;
+            Command::SetRegion(region) => {
+                self.region = region;
+                self.rm_api.execute_self(&format!("!WriteKeyValue {} Region {} \"{}\"", self.measure_name, self.region, self.SKIN_PATH));
+                self.rewrite_file()
             },
+            Command::SetLang(language) => {
+                self.language = language;
+                self.locale = self.load_locale();
+                self.rm_api.execute_self(&format!("!WriteKeyValue {} Language {} \"{}\"", self.measure_name, self.language, self.SKIN_PATH));
+                self.rewrite_file()
+            },
+        }
+    }
+    fn select_sche(&mut self, id: &str) -> Result<(), String> {
+        if self.schedules.is_some() && !self.schedule_ids().iter().any(|s| s == id) {
+            return Err(format!("Unknown Schedule: {id}"));
+        }
+        self.sche = id.to_string();
+        self.rm_api.execute_self(&format!("!WriteKeyValue Variables CurrentSche {id} \"#@#{}\"", self.generated_file));
+        self.rm_api.execute_self(&format!("!UpdateMeasure {}", self.measure_name));
+        Ok(())
+    }
+    fn check_alerts(&mut self) {
+        if self.alert_action.is_empty() && self.alert_sound.is_empty() {
+            return;
+        }
+        if self.alerts.rules.is_empty() && self.favorites.is_empty() {
+            return;
+        }
+        let Some(schedules) = self.generated_schedules() else {
+            return;
         };
+        let mut due = self.alerts.due(&self.measure_name, &schedules, &self.favorites);
+        if due.is_empty() {
+            return;
+        }
+        // Another skin with the same measure name may have fired some of these already
+        let path = format!("{}/{ALERTS_FILE_NAME}", self.RESOURCE_DIR);
+        self.alerts.merge_fired(&path);
+        due.retain(|alert| !self.alerts.fired.contains(&alert.key));
+        for alert in due {
+            self.rm_api.log(crate::rainmeter::api::LogType::Notice, format!("Alert: {}", alert.text));
+            // Kept as a variable so meters can show the last alert
+            self.rm_api.execute_self(&format!("!SetVariable LastAlert \"\"\"{}\"\"\"", alert.text));
+            if !self.alert_action.is_empty() {
+                self.rm_api.execute_self(&self.alert_action.replace("$Alert$", &alert.text));
+            }
+            if !self.alert_sound.is_empty() {
+                self.rm_api.execute_self(&format!("!Play \"{}\"", self.alert_sound));
+            }
+            self.alerts.fired.push(alert.key);
+        }
+        let _ = self.alerts.save_fired(&path)
+            .map_err(|e| self.rm_api.log(crate::rainmeter::api::LogType::Warning, e));
+    }
+    fn schedule_ids(&mut self) -> Vec<String> {
+        match self.generated_schedules() {
+            Some(schedules) => schedules.iter().map(|s| s.get_id().to_string())
+                .chain(self.show_stats.then(|| "StatsSche".to_string()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+    // Section functions can be called many times per update, so the schedules are only
+    // generated again when the data or the language changed
+    fn generated_schedules(&mut self) -> Option<Schedules> {
+        let data = self.schedules.as_ref()?;
+        if let Some((version, language, schedules)) = &self.generated {
+            if *version == self.data_version && *language == self.language {
+                return Some(schedules.clone());
+            }
+        }
+        let schedules = Rc::new(RmStructure::generate_schedules(&self.locale.apply(data), CoopFilter::All));
+        self.generated = Some((self.data_version, self.language.clone(), schedules.clone()));
+        Some(schedules)
+    }
+    fn load_locale(&self) -> Locale {
+        if self.language == DEFAULT_LANGUAGE {
+            return Locale::default();
+        }
+        let path = format!("{}/{}", self.RESOURCE_DIR, Locale::file_name(&self.language));
+        std::fs::read_to_string(&path)
+            .or_else(|_| {
+                self.rm_api.log(crate::rainmeter::api::LogType::Notice, format!("Pulling {} locale from web...", self.language));
+                let json = self.client()?.get(Locale::url(&self.language)).send().map_err(|e| format!("Failed To Fetch Json: {e:?}"))?
+                    .text().map_err(|e| format!("Failed To Build Text: {e:?}"))?;
+                let _ = std::fs::write(&path, &json);
+                Ok(json)
+            })
+            .and_then(|json| Self::parse_json::<Locale>(&json))
+            .unwrap_or_else(|e: String| {
+                self.rm_api.log(crate::rainmeter::api::LogType::Warning, e);
+                Locale::default()
+            })
+    }
+    pub fn update(&mut self) -> f64 {
+        if SCHEDULE_JSON_SOURCE == JsonSource::Web {
+            self.check();
+        }
+        let minute = chrono::Local::now().timestamp() / 60;
+        if minute != self.alert_minute {
+            self.alert_minute = minute;
+            self.check_alerts();
+        }
+        if self.sche != self.prev_sche && self.columns.is_empty() {
+            self.rm_api.execute_self(&format!("!HideMeterGroup {}", self.prev_sche));
+            self.rm_api.execute_self(&format!("!ShowMeterGroup {}", self.sche));
+            // Challenge details panels are in the schedule's group too but stay closed until clicked
+            self.rm_api.execute_self("!HideMeterGroup Details");
+            self.rm_api.execute_self("!Redraw");
+            self.prev_sche = self.sche.clone();
+        }
         0.5
     }
     pub fn get_string(&mut self) -> Option<String> {
-        match self.measure_type {
-            SplatinkType::Core(_) => Some(self.prev_sche.clone()),
-            SplatinkType::TimeBar(_) => None,
+        Some(self.prev_sche.clone())
+    }
+
+    // Inline section variable functions, e.g. [&SplatinkCore:Current(RegSche, Stage1)]
+    pub fn section_function(&mut self, name: &str, args: &[String]) -> Option<String> {
+        let data = self.schedules.clone()?;
+        let schedules = self.generated_schedules()?;
+        let sche = |id: &str| schedules.iter().find(|s| s.get_id().eq_ignore_ascii_case(id.trim()));
+        match (name, args) {
+            ("current", [id, field]) => sche(id)?.event(0)?.query_time(field.trim()),
+            ("next", [id, field]) => sche(id)?.event(1)?.query_time(field.trim()),
+            ("next", [id, field, n]) => sche(id)?.event(n.trim().parse().ok()?)?.query_time(field.trim()),
+            ("timeleft", [id]) => {
+                // Until the current rotation ends, or until the next one starts during a gap
+                let (start, end) = sche(id)?.event(0)?.run_time()?;
+                let now = chrono::Local::now();
+                Some(format_duration(if now < start {start - now} else {end - now}))
+            },
+            ("previous", [id, field]) => self.history.previous(id.trim(), 1).first()?.query_time(field.trim()),
+            ("previous", [id, field, n]) => {
+                let n: usize = n.trim().parse().ok().filter(|n| *n > 0)?;
+                self.history.previous(id.trim(), n).get(n - 1)?.query_time(field.trim())
+            },
+            ("historycount", [days, filter]) => {
+                let filter = filter.parse().ok()?;
+                Some(self.history.matching(days.trim().parse().ok()?, &filter).len().to_string())
+            },
+            ("feststate", []) => Some(data.data.currentFest.as_ref().map(|f| f.state.clone()).unwrap_or("NONE".to_string())),
+            _ => None,
         }
     }
 
     fn check(&mut self) {
+        // Another instance may have pulled newer data already
+        let (shared, version) = store::with(|s| (s.schedules.clone(), s.version));
+        if version != self.data_version {
+            let had_schedules = self.schedules.is_some();
+            self.schedules = shared;
+            self.data_version = version;
+            self.record_history();
+            if had_schedules && self.schedules.is_some() {
+                self.rewrite_file().map_err(|e| self.rm_api.log(crate::rainmeter::api::LogType::Error, e)).ok();
+            }
+        }
         if let None = self.schedules {
             self.populate_schedules();
         }
+        let (web_pull_cooldown, web_pull_cooldown_set, repull_requested) = store::with(|s| (s.web_pull_cooldown(), s.web_pull_cooldown_set, s.repull_requested));
         if let Some(ref schedules) = self.schedules {
             match schedules.data.regularSchedules.nodes.first().ok_or("Local Regular Schedule Has No Elements".to_string())
                 .and_then(|event|{
-                    if SCHEDULE_JSON_SOURCE != JsonSource::Web || chrono::Local::now() > event.endTime {
-                        if self.web_pull_cooldown_set == 2 {self.rm_api.log(crate::rainmeter::api::LogType::Notice, "--------Schedules out of date--------");}
-                        if !self.web_pull_cooldown.is_zero() {
+                    if SCHEDULE_JSON_SOURCE != JsonSource::Web || chrono::Local::now() > event.endTime || repull_requested {
+                        if web_pull_cooldown_set == 2 {self.rm_api.log(crate::rainmeter::api::LogType::Notice, "--------Schedules out of date--------");}
+                        if !web_pull_cooldown.is_zero() {
                             Ok(Err(false))
                         } else {
                             self.pull_schedules()
                             .and_then(|json|
                                 Self::parse_json::<RotationData>(&json)
                             )
+                            .inspect(|_| store::with(|s| s.repull_requested = false))
                             .and_then(|source| {
                                 match SCHEDULE_JSON_SOURCE {
                                     JsonSource::Web => {
@@ -154,7 +481,7 @@ impl Measure {
                                         }
                                     },
                                     JsonSource::Storage(_) => {
-                                        if &source != schedules {
+                                        if &source != schedules.as_ref() {
                                             Ok(Ok(source))
                                         } else {Ok(Err(false))}
                                     }
@@ -164,7 +491,7 @@ impl Measure {
                     } else {Ok(Err(false))}
                 }) {
                     Ok(Ok(replacement)) => {
-                        self.schedules = Some(replacement);
+                        self.set_schedules(replacement);
                         self.rewrite_file().map_err(|e| self.rm_api.log(crate::rainmeter::api::LogType::Error, e)).ok();
                     }
                     varible => {
@@ -176,16 +503,12 @@ impl Measure {
                                     Ok(Err(true)) => {self.rm_api.log(crate::rainmeter::api::LogType::Warning, "Web schedule hasn't been updated yet".to_string());}
                                     _ => {}
                                 }
-                                if self.web_pull_cooldown.is_zero() {
-                                    self.web_pull_cooldown = Duration::seconds(2_i64.pow(self.web_pull_cooldown_set));
-                                    self.web_pull_cooldown_set = (self.web_pull_cooldown_set + 1).min(10);
-                                    self.rm_api.log(crate::rainmeter::api::LogType::Notice, format!("Web requesting on cooldown for {:02}:{:02}", self.web_pull_cooldown.num_minutes(), self.web_pull_cooldown.num_seconds() % 60));
+                                if web_pull_cooldown.is_zero() {
+                                    let cooldown = store::with(|s| s.start_web_pull_cooldown());
+                                    self.rm_api.log(crate::rainmeter::api::LogType::Notice, format!("Web requesting on cooldown for {:02}:{:02}", cooldown.num_minutes(), cooldown.num_seconds() % 60));
                                 }
                             }
                         }
-                        if !self.web_pull_cooldown.is_zero() {
-                            self.web_pull_cooldown = self.web_pull_cooldown - Duration::seconds(1);
-                        }
                     }
                 }
         }
@@ -196,7 +519,7 @@ impl Measure {
     }
 
     fn populate_schedules(&mut self) {
-        self.schedules = self.read_local_schedules()
+        let schedules = self.read_local_schedules()
             .or_else(|e| {
                 self.rm_api.log(crate::rainmeter::api::LogType::Warning, e);
                 if let JsonSource::Web = SCHEDULE_JSON_SOURCE {
@@ -210,6 +533,47 @@ impl Measure {
             )
             .map_err(|e| self.rm_api.log(crate::rainmeter::api::LogType::Error, e))
             .ok();
+        if let Some(schedules) = schedules {
+            self.set_schedules(schedules);
+        }
+    }
+
+    // Saves new data locally and hands it to the other instances, which rewrite
+    // their skins on their next update
+    fn set_schedules(&mut self, schedules: RotationData) {
+        let _ = serde_json::to_string(&schedules).map_err(|e| format!("Failed To Serialize: {e:?}"))
+            .and_then(|serialized_json|
+                std::fs::write(format!("{}/{SCHEDULE_JSON_NAME}", self.RESOURCE_DIR), serialized_json)
+                    .map_err(|e| format!("Failed To Write To File: {e:?}"))
+            )
+            .map_err(|e| self.rm_api.log(crate::rainmeter::api::LogType::Error, e));
+        (self.schedules, self.data_version) = store::with(|s| s.set_schedules(schedules));
+        // The other feeds go with the new schedules, pulled once here for every instance
+        for result in [self.shared_splatfests().map(|_| ()), self.shared_releases().map(|_| ())] {
+            let _ = result.map_err(|e| self.rm_api.log(crate::rainmeter::api::LogType::Warning, e));
+        }
+        self.record_history();
+    }
+
+    // Adds the rotations in the current data to the history file. The file is read again
+    // first since other instances write to it too
+    fn record_history(&mut self) {
+        let Some(schedules) = self.generated_schedules() else {
+            return;
+        };
+        let path = format!("{}/{HISTORY_FILE_NAME}", self.RESOURCE_DIR);
+        let result = History::load(&path).and_then(|mut history| {
+            let changed = history.record(&schedules);
+            if changed {
+                history.prune();
+                history.save(&path)?;
+            }
+            self.history = history;
+            Ok(())
+        });
+        if let Err(e) = result {
+            self.rm_api.log(crate::rainmeter::api::LogType::Warning, e);
+        }
     }
 
     fn read_local_schedules(&self) -> Result<String, String> {
@@ -217,11 +581,15 @@ impl Measure {
         std::fs::read_to_string(format!("{}/{SCHEDULE_JSON_NAME}", self.RESOURCE_DIR)).map_err(|e| format!("Failed To Read File: {e:?}"))
     }
 
+    fn client(&self) -> Result<Client, String> {
+        store::with(|s| s.client.clone()).ok_or("Failed To Build Web Client".to_string())
+    }
+
     fn pull_schedules(&self) -> Result<String, String> {
         match SCHEDULE_JSON_SOURCE {
             JsonSource::Web => {
                 self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Pulling schedules from web...");
-                self.web_client.get(SCHEDULE_URL).send().map_err(|e| format!("Failed To Fetch Json: {e:?}"))?
+                self.client()?.get(SCHEDULE_URL).send().map_err(|e| format!("Failed To Fetch Json: {e:?}"))?
                     .text().map_err(|e| format!("Failed To Build Text: {e:?}"))
             },
             JsonSource::Storage(name) => {
@@ -235,7 +603,7 @@ impl Measure {
         match SPLATFEST_JSON_SOURCE {
             JsonSource::Web => {
                 self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Pulling splatfests from web...");
-                self.web_client.get(SPLATFEST_URL).send().map_err(|e| format!("Failed To Fetch Json: {e:?}"))?
+                self.client()?.get(SPLATFEST_URL).send().map_err(|e| format!("Failed To Fetch Json: {e:?}"))?
                     .text().map_err(|e| format!("Failed To Build Text: {e:?}"))
             },
             JsonSource::Storage(name) => {
@@ -247,68 +615,172 @@ impl Measure {
 
     fn pull_releases(&self) -> Result<String, String> {
         self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Pulling releases from web...");
-        self.web_client.get(GITHUB_RELEASES_URL).send().map_err(|e| format!("Failed To Fetch Json: {e:?}"))?
+        self.client()?.get(GITHUB_RELEASES_URL).send().map_err(|e| format!("Failed To Fetch Json: {e:?}"))?
             .text().map_err(|e| format!("Failed To Build Text: {e:?}"))
     }
 
+    // Splatfests and releases are shared like the schedules, so they're only pulled
+    // when no instance has them since the schedules last changed
+    fn shared_splatfests(&self) -> Result<Arc<SplatfestData>, String> {
+        if let Some(splatfests) = store::with(|s| s.splatfests.clone()) {
+            return Ok(splatfests);
+        }
+        let splatfests = Arc::new(self.pull_splatfests().and_then(|json| Self::parse_json::<SplatfestData>(&json))?);
+        store::with(|s| s.splatfests = Some(splatfests.clone()));
+        Ok(splatfests)
+    }
+
+    fn shared_releases(&self) -> Result<Arc<Releases>, String> {
+        if let Some(releases) = store::with(|s| s.releases.clone()) {
+            return Ok(releases);
+        }
+        let releases = Arc::new(self.pull_releases().and_then(|json| Self::parse_json::<Releases>(&json))?);
+        store::with(|s| s.releases = Some(releases.clone()));
+        Ok(releases)
+    }
+
     fn rewrite_file(&self) -> Result<(), String> {
         self.schedules.as_ref().ok_or("Failed To Rewrite File: No Schedule".to_string())
-            .and_then(|schedules|                       // Write internal to Local
-                serde_json::to_string(schedules).map_err(|e| format!("Failed To Serialize: {e:?}"))
-                    .and_then(|serialized_json|
-                        std::fs::write(
-                            format!("{}/{SCHEDULE_JSON_NAME}", self.RESOURCE_DIR),
-                            serialized_json
-                        ).map_err(|e| format!("Failed To Write To File: {e:?}"))
-                        .map(|_| schedules)
-                    )
-            )
             .and_then(|schedules|                       // Build Structure
-                self.pull_splatfests()
-                    .and_then(|json|
-                        Self::parse_json::<SplatfestData>(&json)
-                    )
-                    .map(|splatfests| (schedules, splatfests))
+                self.shared_splatfests()
+                    .map(|splatfests| (self.locale.apply(schedules), splatfests))
                 )
             .and_then(|(schedules, splatfests)|
-                self.pull_releases()
-                    .and_then(|json|
-                        Self::parse_json::<Releases>(&json)
-                    )
+                self.shared_releases()
                     .map(|releases| (schedules, splatfests, releases))
                 )
             .map(|(schedules, splatfests, releases)| {
                 self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Building Structure...");
-                RmStructure::generate(schedules, &splatfests, &releases)
+                let mut structure = RmStructure::generate(&schedules, splatfests.region(&self.region), &releases, self.coop_filter);
+                if self.previous_rotations > 0 {
+                    for sche in structure.schedules.iter_mut() {
+                        sche.set_history(self.history.previous(sche.get_id(), self.previous_rotations));
+                    }
+                }
+                if self.show_stats {
+                    structure.schedules.push(Box::new(Stats::compute(&self.history, self.stats_days).to_schedule()));
+                    RmStructure::link_schedules(&mut structure.schedules);
+                }
+                structure
+            })
+            .map(|structure| {
+                let theme = Theme::load(&format!("{}/{THEME_FILE_NAME}", self.RESOURCE_DIR), &self.theme_preset)
+                    .unwrap_or_else(|e| {
+                        self.rm_api.log(crate::rainmeter::api::LogType::Warning, e);
+                        Theme::preset(&self.theme_preset).unwrap_or_default()
+                    });
+                (structure, Style{
+                    theme,
+                    scale: self.scale,
+                    layout: self.layout,
+                    orientation: self.orientation,
+                    columns: self.columns.clone(),
+                    output: self.output,
+                    core_measure: self.measure_name.clone(),
+                    favorites: self.favorites.clone(),
+                    weapons: self.weapons.clone(),
+                })
             })
-            .map(|structure|{                           // Download Images
+            .map(|(structure, style)|{                  // Download Images
                 self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Downloading missing images...");
                 for ele in structure.download(&self.RESOURCE_DIR) {
                     let _ = ele.map_err(|e| self.rm_api.log(crate::rainmeter::api::LogType::Warning, e));
                 }
-                structure
+                (structure, style)
             })
-            .and_then(|structure| {                     // Write to file
-                self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Rewriting file...");
-                write_to_skin(self.SKIN_PATH.as_str(), {
-                    let mut ret = Vec::new();
-                    ret.push(RmObject::new(ObjectType::Measure(
-                        MeasureType::Plugin(PluginType::Splatink(
-                            if structure.schedules.iter().any(|s| SplatinkType::Core(s.get_id().to_string()) == self.measure_type) {
-                                self.measure_type.clone()
-                            } else {
-                                SplatinkType::Core(match structure.schedules.iter().next() {
-                                    Some(s) => s.get_id().to_string(),
-                                    None => "nonewhat".to_string()
-                                })
-                            }
-                        )),
-                        MeasureOptions::default()
-                    )).prefix_name_owned("SplatinkCore"));
-                    ret.append(&mut structure.to_rm());
-                    ret
-                }).map_err(|e| format!("Failed To Write To File: {e:?}"))
+            .map(|(structure, style)| {                 // Build generated include
+                let selected = if structure.schedules.iter().any(|sche| sche.get_id() == self.sche) {
+                    self.sche.clone()
+                } else {
+                    match structure.schedules.iter().next() {
+                        Some(s) => s.get_id().to_string(),
+                        None => "nonewhat".to_string()
+                    }
+                };
+                let mut variables = vec![("CurrentSche".to_string(), selected)];
+                variables.append(&mut style.theme.variables());
+                // to_rm has its own [Variables] section in variables output
+                merge_skin(vec![RmObject::new(ObjectType::Variables(variables)).prefix_name_owned("Variables")], structure.to_rm(&style))
+            })
+            .map(|objects| {                            // Apply user customizations
+                match read_skin(&format!("{}/{CUSTOMIZATIONS_FILE_NAME}", self.RESOURCE_DIR)) {
+                    Ok(customizations) => merge_skin(objects, customizations),
+                    Err(_) => objects,
+                }
+            })
+            .and_then(|objects| {                       // Write to file
+                let created = self.write_main_skin()?;
+                let generated_path = format!("{}/{}", self.RESOURCE_DIR, self.generated_file);
+                let main = read_skin(&self.SKIN_PATH).unwrap_or_default();
+                for problem in validate_skin(main.iter().chain(objects.iter())) {
+                    self.rm_api.log(crate::rainmeter::api::LogType::Warning, problem);
+                }
+                let old = read_skin(&generated_path);
+                let diff = old.as_ref().map(|old| diff_skins(old, &objects));
+                if !created && diff.as_ref().is_ok_and(|d| d.is_empty()) {
+                    self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Skin unchanged");
+                    return Ok(false);
+                }
+                if let (false, Ok(old), Ok(true)) = (created, &old, diff.as_ref().map(|d| d.only_in("Variables"))) {
+                    // Same meters as before, so the running skin only needs the new values
+                    self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Updating variables...");
+                    write_to_skin(&generated_path, &objects).map_err(|e| format!("Failed To Write To File: {e:?}"))?;
+                    for (key, value) in changed_variables(old, &objects) {
+                        self.rm_api.execute_self(&format!("!SetVariable {key} \"\"\"{value}\"\"\""));
+                    }
+                    self.rm_api.execute_self("!Update");
+                    self.rm_api.execute_self("!Redraw");
+                    return Ok(false);
+                }
+                self.rm_api.log(crate::rainmeter::api::LogType::Notice, match diff {
+                    Ok(d) => format!("Rewriting file ({d})..."),
+                    Err(_) => "Rewriting file...".to_string(),
+                });
+                write_to_skin(&generated_path, &objects)
+                    .map(|_| true)
+                    .map_err(|e| format!("Failed To Write To File: {e:?}"))
             })
-            .map(|_| self.rm_api.execute_self("!Refresh"))
+            .map(|changed| if changed {self.rm_api.execute_self("!Refresh")})
+    }
+
+    // The main skin file belongs to the user once it exists. It's only written when
+    // missing, or replaced when it's still a whole generated skin from before Generated.inc
+    fn write_main_skin(&self) -> Result<bool, String> {
+        match std::fs::read_to_string(&self.SKIN_PATH) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Writing main skin file...");
+            },
+            Err(e) => return Err(format!("Failed To Read Main Skin: {e:?}")),
+            Ok(contents) if is_single_file_skin(&contents) => {
+                self.rm_api.log(crate::rainmeter::api::LogType::Notice, "Moving old skin file to the generated include...");
+            },
+            Ok(contents) => {
+                if !contents.contains(&self.generated_file) {
+                    self.rm_api.log(crate::rainmeter::api::LogType::Warning, format!("Main skin doesn't include {}, leaving it as is", self.generated_file));
+                }
+                return Ok(false);
+            },
+        }
+        let mut ret = skin_header(&self.measure_name, &self.generated_file);
+        ret.push(RmObject::new(ObjectType::Measure(
+            MeasureType::Plugin(PluginType::Splatink(SplatinkType::Core("#CurrentSche#".to_string()))),
+            MeasureOptions {
+                other: vec![
+                    ("Theme".to_string(), self.theme_preset.clone()),
+                    ("Scale".to_string(), self.scale.to_string()),
+                    ("Layout".to_string(), self.layout.name().to_string()),
+                    ("Orientation".to_string(), self.orientation.name().to_string()),
+                    ("Columns".to_string(), self.columns.join("|")),
+                    ("Output".to_string(), self.output.name().to_string()),
+                    ("Region".to_string(), self.region.clone()),
+                    ("Language".to_string(), self.language.clone()),
+                    ("GeneratedFile".to_string(), self.generated_file.clone()),
+                ],
+                ..MeasureOptions::default()
+            }
+        )).prefix_name_owned(&self.measure_name));
+        write_to_skin(&self.SKIN_PATH, &ret)
+            .map(|_| true)
+            .map_err(|e| format!("Failed To Write Main Skin: {e:?}"))
     }
 }
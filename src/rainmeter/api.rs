@@ -15,34 +15,32 @@ impl RmApi {
     def_value: &str,
     replace_measures: Option<bool>,
   ) -> String {
+    let option = option.to_wchar_vec();
+    let def_value = def_value.to_wchar_vec();
     unsafe {
       String::from_wchar_ptr(RmReadString(
         self.rm,
-        option.to_wchar_vec().as_ptr(),
-        def_value.to_wchar_vec().as_ptr(),
+        option.as_ptr(),
+        def_value.as_ptr(),
         replace_measures.unwrap_or(true),
       ))
     }
   }
   pub fn read_path(&self, option: &str, def_value: &str) -> String {
-    unsafe {
-      String::from_wchar_ptr(RmPathToAbsolute(
-        self.rm,
-        self
-          .read_string(option, def_value, None)
-          .to_wchar_vec()
-          .as_ptr(),
-      ))
-    }
+    let path = self.read_string(option, def_value, None).to_wchar_vec();
+    unsafe { String::from_wchar_ptr(RmPathToAbsolute(self.rm, path.as_ptr())) }
   }
   pub fn read_double(&self, option: &str, def_value: f64) -> f64 {
-    unsafe { RmReadFormula(self.rm, option.to_wchar_vec().as_ptr(), def_value) }
+    let option = option.to_wchar_vec();
+    unsafe { RmReadFormula(self.rm, option.as_ptr(), def_value) }
   }
   pub fn read_int(&self, option: &str, def_value: i32) -> i32 {
-    unsafe { RmReadFormula(self.rm, option.to_wchar_vec().as_ptr(), def_value as f64) as i32 }
+    let option = option.to_wchar_vec();
+    unsafe { RmReadFormula(self.rm, option.as_ptr(), def_value as f64) as i32 }
   }
   pub fn replace_variables(&self, str: &str) -> String {
-    unsafe { String::from_wchar_ptr(RmReplaceVariables(self.rm, str.to_wchar_vec().as_ptr())) }
+    let str = str.to_wchar_vec();
+    unsafe { String::from_wchar_ptr(RmReplaceVariables(self.rm, str.as_ptr())) }
   }
   pub fn get_measure_name(&self) -> String {
     unsafe { String::from_wchar_ptr(RmGet(self.rm, RmGetType::MeasureName) as *mut wchar_t) }
@@ -57,21 +55,23 @@ impl RmApi {
     unsafe { String::from_wchar_ptr(RmGet(self.rm, RmGetType::SkinName) as *mut wchar_t) }
   }
   pub fn execute(&self, skin: RmData, command: &str) {
-    unsafe { RmExecute(skin, command.to_wchar_vec().as_ptr()) }
+    let command = command.to_wchar_vec();
+    unsafe { RmExecute(skin, command.as_ptr()) }
   }
   pub fn execute_self(&self, command: &str) {
-    unsafe { RmExecute(self.get_skin(), command.to_wchar_vec().as_ptr()) }
+    let command = command.to_wchar_vec();
+    unsafe { RmExecute(self.get_skin(), command.as_ptr()) }
   }
   pub fn get_skin_window(&self) -> RmData {
     unsafe { RmGet(self.rm, RmGetType::SkinWindowHandle) }
   }
   pub fn log<T: Into<String>>(&self, log_type: LogType, message: T) -> i32 {
-    let message: String = message.into();
-    unsafe { RmLog(self.rm, log_type, message.to_wchar_vec().as_ptr()) }
+    let message = message.into().to_wchar_vec();
+    unsafe { RmLog(self.rm, log_type, message.as_ptr()) }
   }
   pub fn ls_log<T: Into<String>>(log_type: LogType, message: T) -> i32 {
-    let message: String = message.into();
-    unsafe { LSLog(log_type, std::ptr::null(), message.to_wchar_vec().as_ptr()) }
+    let message = message.into().to_wchar_vec();
+    unsafe { LSLog(log_type, std::ptr::null(), message.as_ptr()) }
   }
 }
 
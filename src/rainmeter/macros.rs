@@ -29,10 +29,13 @@ macro_rules! rm_parse_string {
 }
 
 #[macro_export]
+// Rainmeter reads the returned pointer after the export returns, so the UTF-16
+// string is kept in `$buffer` (owned by the measure) until the next call
 macro_rules! rm_to_string {
-  ($data: expr) => {
-    $data.to_wchar_vec().as_ptr()
-  };
+  ($buffer: expr, $data: expr) => {{
+    $buffer = $data.to_wchar_vec();
+    $buffer.as_ptr()
+  }};
 }
 
 #[macro_export]
@@ -28,7 +28,8 @@ impl Wchar for str {
   }
 }
 
-// If we call .to_ptr() here, it will sometimes get deallocated too early.
+// If we call .as_ptr() here, the Vec is dropped before the pointer is read.
+// Callers must keep the Vec alive for as long as the pointer is in use
 fn to_wchar_vec<T: Into<String>>(str: T) -> Vec<wchar_t> {
   let str: String = str.into();
   str.encode_utf16().chain(Some(0)).collect::<Vec<wchar_t>>()
@@ -0,0 +1,185 @@
+use crate::rm_write::{Coord, RmObject, ObjectType, MeterType, StringOptions};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Align {
+    Start,
+    Center,
+}
+impl Align {
+    fn offset(&self, free_space: isize) -> isize {
+        match self {
+            Align::Start => 0,
+            Align::Center => free_space / 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    Row,
+    Column,
+    Stack,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Padding {
+    pub top: isize,
+    pub right: isize,
+    pub bottom: isize,
+    pub left: isize,
+}
+impl From<isize> for Padding {
+    fn from(value: isize) -> Self {
+        Padding { top: value, right: value, bottom: value, left: value }
+    }
+}
+impl From<(isize, isize)> for Padding {
+    fn from(value: (isize, isize)) -> Self {
+        Padding { top: value.1, right: value.0, bottom: value.1, left: value.0 }
+    }
+}
+
+// Top left corner of the box a meter covers, accounting for string alignment
+// since X/Y of an aligned string is its anchor point rather than its corner
+fn meter_origin(t: &MeterType, pos: &Coord, size: &Coord) -> Coord {
+    match t {
+        MeterType::String(StringOptions { string_align: Some(align), .. }) => {
+            let (x, y) = align.anchor();
+            (pos.x - size.x * x / 2, pos.y - size.y * y / 2).into()
+        },
+        _ => pos.clone(),
+    }
+}
+
+// A group of objects placed relative to (0,0) that takes up `size`
+pub struct Block {
+    pub objects: Vec<RmObject>,
+    pub size: Coord,
+}
+impl Block {
+    pub fn new(objects: Vec<RmObject>) -> Self {
+        let mut size: Coord = (0,0).into();
+        for obj in objects.iter() {
+            if let ObjectType::Meter(ref t, ref o) = obj.object_type {
+                let origin = meter_origin(t, &o.pos, &o.size);
+                size.x = size.x.max(origin.x + o.size.x);
+                size.y = size.y.max(origin.y + o.size.y);
+            }
+        }
+        Block { objects, size }
+    }
+    pub fn sized(objects: Vec<RmObject>, size: impl Into<Coord>) -> Self {
+        Block { objects, size: size.into() }
+    }
+    pub fn offset(&mut self, by: Coord) {
+        for obj in self.objects.iter_mut() {
+            if let ObjectType::Meter(_, ref mut o) = obj.object_type {
+                o.pos += by.clone();
+            }
+        }
+    }
+    // Scales positions, sizes and font sizes uniformly
+    pub fn scale(&mut self, factor: f64) {
+        let scale = |v: isize| (v as f64 * factor).round() as isize;
+        for obj in self.objects.iter_mut() {
+            if let ObjectType::Meter(ref mut t, ref mut o) = obj.object_type {
+                o.pos = (scale(o.pos.x), scale(o.pos.y)).into();
+                o.size = (scale(o.size.x), scale(o.size.y)).into();
+                match t {
+                    MeterType::String(ref mut s) => s.font_size = s.font_size.map(|f| f * factor),
+                    MeterType::Shape(ref mut s) => s.shapes.iter_mut().for_each(|shape| shape.kind.scale(factor)),
+                    _ => {},
+                }
+            }
+        }
+        self.size = (scale(self.size.x), scale(self.size.y)).into();
+    }
+    pub fn into_objects(self) -> Vec<RmObject> {
+        self.objects
+    }
+}
+impl From<Vec<RmObject>> for Block {
+    fn from(value: Vec<RmObject>) -> Self {
+        Block::new(value)
+    }
+}
+impl From<RmObject> for Block {
+    fn from(value: RmObject) -> Self {
+        Block::new(vec![value])
+    }
+}
+
+// Places child blocks next to each other (row), under each other (column) or
+// on top of each other (stack). Alignment is along the cross axis for rows and
+// columns, and along both axes for stacks.
+pub struct Container {
+    direction: Direction,
+    align: Align,
+    padding: Padding,
+    children: Vec<Block>,
+}
+impl Container {
+    fn new(direction: Direction) -> Self {
+        Container {
+            direction,
+            align: Align::Start,
+            padding: Padding::default(),
+            children: Vec::new(),
+        }
+    }
+    pub fn row() -> Self {
+        Container::new(Direction::Row)
+    }
+    pub fn column() -> Self {
+        Container::new(Direction::Column)
+    }
+    pub fn stack() -> Self {
+        Container::new(Direction::Stack)
+    }
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+    pub fn padding(mut self, padding: impl Into<Padding>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+    pub fn push(mut self, child: impl Into<Block>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+    pub fn push_all<T: Into<Block>>(mut self, children: impl IntoIterator<Item = T>) -> Self {
+        for child in children {
+            self.children.push(child.into());
+        }
+        self
+    }
+    pub fn build(self) -> Block {
+        let cross: Coord = self.children.iter().fold((0,0).into(), |acc: Coord, c| (acc.x.max(c.size.x), acc.y.max(c.size.y)).into());
+        let mut objects = Vec::new();
+        let mut cursor: isize = 0;
+        for mut child in self.children.into_iter() {
+            let offset: Coord = match self.direction {
+                Direction::Row => (cursor, self.align.offset(cross.y - child.size.y)),
+                Direction::Column => (self.align.offset(cross.x - child.size.x), cursor),
+                Direction::Stack => (self.align.offset(cross.x - child.size.x), self.align.offset(cross.y - child.size.y)),
+            }.into();
+            cursor += match self.direction {
+                Direction::Row => child.size.x,
+                Direction::Column => child.size.y,
+                Direction::Stack => 0,
+            };
+            child.offset(offset + (self.padding.left, self.padding.top).into());
+            objects.append(&mut child.objects);
+        }
+        let content: Coord = match self.direction {
+            Direction::Row => (cursor, cross.y),
+            Direction::Column => (cross.x, cursor),
+            Direction::Stack => (cross.x, cross.y),
+        }.into();
+        Block {
+            objects,
+            size: content + (self.padding.left + self.padding.right, self.padding.top + self.padding.bottom).into(),
+        }
+    }
+}
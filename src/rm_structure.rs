@@ -1,7 +1,11 @@
 extern crate chrono;
-use std::ops::{Div, Mul, Not};
+use std::ops::{Div, Mul};
 
-use crate::rm_write::{Color, ToRM, RmObject, ObjectType, MeterType, StringOptions, MeterOptions, ImageOptions, MeasureType, PluginType, SplatinkType, TimeBarOptions, MeasureOptions, BarOptions, BarOrientation, ToolTip};
+use crate::rm_write::{Color, ToRM, RmObject, ObjectType, MeterType, MeterOptions, ImageOptions, MeasureType, PluginType, SplatinkType, TimeBarOptions, MeasureOptions, BarOptions, BarOrientation, ToolTip, Coord, ShapeOptions, Shape, StringAlign};
+use crate::rm_layout::{Align, Block, Container};
+use crate::markup;
+use crate::history::HistoryEntry;
+use crate::style::{CoopFilter, LayoutMode, Orientation, OutputMode, Style};
 
 const DISPLAY_TIME_FORMAT: &str = "%a %-I%P";
 const TOOLTIP_TIME_FORMAT: &str = "%-m/%-d %-I%P";
@@ -28,15 +32,8 @@ impl Image {
     pub fn download(&self, name: &str, dir_path: &str) -> Result<(), String> {
         let path = format!("{dir_path}/{name}.png");
         if !std::fs::read_dir(dir_path).map_err(|e| format!("Failed To Read Directory: {e:?}"))?
-            .map(|x|
-                x.unwrap()
-                .file_name()
-                .to_str()
-                .unwrap()
-                .to_string()
-            )
-            .collect::<Vec<_>>()
-            .contains(&path)
+            .filter_map(|x| x.ok())
+            .any(|x| x.file_name().to_string_lossy() == format!("{name}.png"))
         {
             std::fs::write(path, reqwest::blocking::get(&self.url)
                 .map_err(|e| format!("Failed To Fetch {name}: {e:?}"))?
@@ -48,7 +45,36 @@ impl Image {
     }
 }
 
-fn new_timebar(start_time: &DateTime<Local>, end_time: &DateTime<Local>) -> Vec<RmObject> {
+// Rounded background panel drawn with a shape meter
+fn new_panel(size: Coord, color: Color) -> RmObject {
+    RmObject::new(ObjectType::meter(
+        MeterType::Shape(
+            ShapeOptions::default().with_shape(Shape::rounded_rectangle(0, 0, size.x, size.y, 8).fill(color).no_stroke())
+        ),
+        MeterOptions::new().with_size(size)
+    ))
+}
+
+// Translucent cover that fades out whatever is under it, for rotations that are over
+fn new_shade(size: Coord) -> RmObject {
+    new_panel(size, (0,0,0,150).into())
+}
+
+// Outline drawn over a block so the cells under it keep their colors
+fn new_outline(size: Coord, color: Color) -> RmObject {
+    RmObject::new(ObjectType::meter(
+        MeterType::Shape(
+            ShapeOptions::default().with_shape(
+                Shape::rounded_rectangle(1, 1, size.x - 2, size.y - 2, 4)
+                    .fill((0,0,0,0).into())
+                    .stroke(color, 2.0)
+            )
+        ),
+        MeterOptions::new().with_size(size)
+    ))
+}
+
+fn new_timebar(start_time: &DateTime<Local>, end_time: &DateTime<Local>, style: &Style) -> Vec<RmObject> {
     let mut ret = Vec::new();
     ret.push(RmObject::new(ObjectType::Measure(
         MeasureType::Plugin(
@@ -56,64 +82,77 @@ fn new_timebar(start_time: &DateTime<Local>, end_time: &DateTime<Local>) -> Vec<
                 SplatinkType::TimeBar(TimeBarOptions {
                     start_time: start_time.timestamp(),
                     end_time: end_time.timestamp(),
+                    variable: None,
                 })
             )
         ),
         MeasureOptions::default()
     )).prefix_name_owned("TimeBarMeasure"));
-    ret.push(RmObject::new(ObjectType::Meter(
+    ret.push(RmObject::new(ObjectType::meter(
         MeterType::Bar(
-            BarOptions {
-                bar_color: (150,50,50,255).into(),
-                bar_orientation: BarOrientation::Horizontal
-            }
+            BarOptions::new(style.theme.time_bar.clone(), BarOrientation::Horizontal)
         ),
         {
             let mut ret = MeterOptions::new();
             ret.size = (100,50).into();
             ret.measure_name = Some("Measure".to_string());
-            ret.solid_color = Some((50,50,50,255).into());
+            ret.solid_color = Some(style.theme.background.clone());
             ret
         }
     )).prefix_name_owned("TimeBar"));
-    ret.push(RmObject::new(ObjectType::Meter(
+    ret.push(RmObject::new(ObjectType::meter(
         MeterType::String(
             {
-                let mut ret = StringOptions::default();
+                let mut ret = style.theme.string_options();
                 ret.text = start_time.format(DISPLAY_TIME_FORMAT).to_string();
                 ret
             }
         ),
         {
             let mut ret = MeterOptions::new();
-            ret.pos = (25,25).into();
-            ret.size = (50,50).into();
+            ret.pos = (25,18).into();
+            ret.size = (50,36).into();
             ret.tool_tip = Some(ToolTip::new(start_time.format(TOOLTIP_TIME_FORMAT).to_string()));
             ret
         }
     )).prefix_name_owned("StartTime"));
-    ret.push(RmObject::new(ObjectType::Meter(
+    ret.push(RmObject::new(ObjectType::meter(
         MeterType::String(
             {
-                let mut ret = StringOptions::default();
+                let mut ret = style.theme.string_options();
                 ret.text = end_time.format(DISPLAY_TIME_FORMAT).to_string();
                 ret
             }
         ),
         {
             let mut ret = MeterOptions::new();
-            ret.pos = (75,25).into();
-            ret.size = (50,50).into();
+            ret.pos = (75,18).into();
+            ret.size = (50,36).into();
             ret.tool_tip = Some(ToolTip::new(end_time.format(TOOLTIP_TIME_FORMAT).to_string()));
             ret
         }
     )).prefix_name_owned("EndTime"));
+    let mut countdown = RmObject::new(ObjectType::meter(
+        MeterType::String(
+            {
+                let mut ret = style.theme.small_string_options();
+                ret.text = "%1".to_string();
+                ret
+            }
+        ),
+        MeterOptions::new().with_pos((50,41)).with_size((100,18))
+    )).prefix_name_owned("TimeBarCountdown");
+    // Reads the bar's measure, which is only named like that once this is named
+    if let ObjectType::Meter(_, ref mut o) = countdown.object_type {
+        o.measure_name = Some("TimeBarMeasure".to_string());
+    }
+    ret.push(countdown);
     ret
 }
 
 impl ToRM for (DateTime<Local>, DateTime<Local>) {
-    fn to_rm(&self) -> Vec<RmObject> {
-        new_timebar(&self.0, &self.1)
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
+        new_timebar(&self.0, &self.1, style)
     }
 }
 
@@ -123,9 +162,8 @@ pub struct RmStructure {
     pub update_notif: Option<UpdateNotification>,
 }
 impl RmStructure {
-    pub fn generate(schedule_data: &crate::schedule_data::RotationData, splatfest_data: &crate::splatfest_data::SplatfestData, releases: &crate::github_data::Releases) -> Self {
+    pub fn generate_schedules(schedule_data: &crate::schedule_data::RotationData, coop_filter: CoopFilter) -> Vec<Box<dyn Sche>> {
         let mut active_schedules: Vec<Box<dyn Sche>> = Vec::new();
-        let mut active_ids = Vec::new();
         //-----------------------------Regular Schedule-----------------------------
         let mut regular_schedule = Vec::new();
         for ele in schedule_data.data.regularSchedules.nodes.iter() {
@@ -140,12 +178,14 @@ impl RmStructure {
                             vs_stages: (
                                 Stage{
                                     name: setting.vsStages.0.name.clone(),
+                                    id: setting.vsStages.0.id.clone(),
                                     image: Image{
                                         url: setting.vsStages.0.image.url.clone()
                                     }
                                 },
                                 Stage{
                                     name: setting.vsStages.1.name.clone(),
+                                    id: setting.vsStages.1.id.clone(),
                                     image: Image{
                                         url: setting.vsStages.1.image.url.clone()
                                     }
@@ -157,7 +197,6 @@ impl RmStructure {
             }
         }
         if !regular_schedule.is_empty() {
-            active_ids.push("RegSche".to_string());
             active_schedules.push(
                 Box::new(
                     Schedule::<VsEvent> {
@@ -165,6 +204,7 @@ impl RmStructure {
                         id: "RegSche".to_string(),
                         prev_sche: String::new(),
                         next_sche: String::new(),
+                        history: Vec::new(),
                         events: regular_schedule,
                     }
                 )
@@ -185,12 +225,14 @@ impl RmStructure {
                             vs_stages: (
                                 Stage{
                                     name: series_setting.vsStages.0.name.clone(),
+                                    id: series_setting.vsStages.0.id.clone(),
                                     image: Image{
                                         url: series_setting.vsStages.0.image.url.clone()
                                     }
                                 },
                                 Stage{
                                     name: series_setting.vsStages.1.name.clone(),
+                                    id: series_setting.vsStages.1.id.clone(),
                                     image: Image{
                                         url: series_setting.vsStages.1.image.url.clone()
                                     }
@@ -209,12 +251,14 @@ impl RmStructure {
                             vs_stages: (
                                 Stage{
                                     name: open_setting.vsStages.0.name.clone(),
+                                    id: open_setting.vsStages.0.id.clone(),
                                     image: Image{
                                         url: open_setting.vsStages.0.image.url.clone()
                                     }
                                 },
                                 Stage{
                                     name: open_setting.vsStages.1.name.clone(),
+                                    id: open_setting.vsStages.1.id.clone(),
                                     image: Image{
                                         url: open_setting.vsStages.1.image.url.clone()
                                     }
@@ -226,7 +270,6 @@ impl RmStructure {
             }
         }
         if !series_schedule.is_empty() {
-            active_ids.push("BanSeriesSche".to_string());
             active_schedules.push(
                 Box::new(
                     Schedule::<VsEvent> {
@@ -234,6 +277,7 @@ impl RmStructure {
                         id: "BanSeriesSche".to_string(),
                         prev_sche: String::new(),
                         next_sche: String::new(),
+                        history: Vec::new(),
                         events: series_schedule,
                     }
                 )
@@ -241,7 +285,6 @@ impl RmStructure {
             
         }
         if !open_schedule.is_empty() {
-            active_ids.push("BanOpenSche".to_string());
             active_schedules.push(
                 Box::new(
                     Schedule::<VsEvent> {
@@ -249,6 +292,7 @@ impl RmStructure {
                         id: "BanOpenSche".to_string(),
                         prev_sche: String::new(),
                         next_sche: String::new(),
+                        history: Vec::new(),
                         events: open_schedule,
                     }
                 )
@@ -268,12 +312,14 @@ impl RmStructure {
                             vs_stages: (
                                 Stage{
                                     name: setting.vsStages.0.name.clone(),
+                                    id: setting.vsStages.0.id.clone(),
                                     image: Image{
                                         url: setting.vsStages.0.image.url.clone()
                                     }
                                 },
                                 Stage{
                                     name: setting.vsStages.1.name.clone(),
+                                    id: setting.vsStages.1.id.clone(),
                                     image: Image{
                                         url: setting.vsStages.1.image.url.clone()
                                     }
@@ -285,7 +331,6 @@ impl RmStructure {
             }
         }
         if !x_schedule.is_empty() {
-            active_ids.push("xSche".to_string());
             active_schedules.push(
                 Box::new(
                     Schedule::<VsEvent> {
@@ -293,6 +338,7 @@ impl RmStructure {
                         id: "xSche".to_string(),
                         prev_sche: String::new(),
                         next_sche: String::new(),
+                        history: Vec::new(),
                         events: x_schedule,
                     }
                 )
@@ -313,12 +359,14 @@ impl RmStructure {
                             vs_stages: (
                                 Stage{
                                     name: open_setting.vsStages.0.name.clone(),
+                                    id: open_setting.vsStages.0.id.clone(),
                                     image: Image{
                                         url: open_setting.vsStages.0.image.url.clone()
                                     }
                                 },
                                 Stage{
                                     name: open_setting.vsStages.1.name.clone(),
+                                    id: open_setting.vsStages.1.id.clone(),
                                     image: Image{
                                         url: open_setting.vsStages.1.image.url.clone()
                                     }
@@ -337,12 +385,14 @@ impl RmStructure {
                             vs_stages: (
                                 Stage{
                                     name: pro_setting.vsStages.0.name.clone(),
+                                    id: pro_setting.vsStages.0.id.clone(),
                                     image: Image{
                                         url: pro_setting.vsStages.0.image.url.clone()
                                     }
                                 },
                                 Stage{
                                     name: pro_setting.vsStages.1.name.clone(),
+                                    id: pro_setting.vsStages.1.id.clone(),
                                     image: Image{
                                         url: pro_setting.vsStages.1.image.url.clone()
                                     }
@@ -354,7 +404,6 @@ impl RmStructure {
             }
         }
         if !splatfest_open_schedule.is_empty() {
-            active_ids.push("SfOpenSche".to_string());
             active_schedules.push(
                 Box::new(
                     Schedule::<VsEvent> {
@@ -362,13 +411,13 @@ impl RmStructure {
                         id: "SfOpenSche".to_string(),
                         prev_sche: String::new(),
                         next_sche: String::new(),
+                        history: Vec::new(),
                         events: splatfest_open_schedule,
                     }
                 )
             );
         }
         if !splatfest_pro_schedule.is_empty() {
-            active_ids.push("SfProSche".to_string());
             active_schedules.push(
                 Box::new(
                     Schedule::<VsEvent> {
@@ -376,6 +425,7 @@ impl RmStructure {
                         id: "SfProSche".to_string(),
                         prev_sche: String::new(),
                         next_sche: String::new(),
+                        history: Vec::new(),
                         events: splatfest_pro_schedule,
                     }
                 )
@@ -383,7 +433,6 @@ impl RmStructure {
         }
         //-----------------------------Challenge Schedule-----------------------------
         if !schedule_data.data.eventSchedules.nodes.is_empty() {
-            active_ids.push("ChalSche".to_string());
             let mut chal_schedule = Vec::new();
             for ele in schedule_data.data.eventSchedules.nodes.iter() {
                 if let Some(rule) = &ele.leagueMatchSetting.vsRule {
@@ -403,12 +452,14 @@ impl RmStructure {
                                 vs_stages: (
                                     Stage{
                                         name: ele.leagueMatchSetting.vsStages.0.name.clone(),
+                                        id: ele.leagueMatchSetting.vsStages.0.id.clone(),
                                         image: Image{
                                             url: ele.leagueMatchSetting.vsStages.0.image.url.clone()
                                         }
                                     },
                                     Stage{
                                         name: ele.leagueMatchSetting.vsStages.1.name.clone(),
+                                        id: ele.leagueMatchSetting.vsStages.1.id.clone(),
                                         image: Image{
                                             url: ele.leagueMatchSetting.vsStages.1.image.url.clone()
                                         }
@@ -416,8 +467,9 @@ impl RmStructure {
                                 )
                             },
                             title: ele.leagueMatchSetting.leagueMatchEvent.name.clone(),
-                            desc: ele.leagueMatchSetting.leagueMatchEvent.desc.replace("<br />", " ").replace("・", " *"),
-                            details: ele.leagueMatchSetting.leagueMatchEvent.regulation.replace("<br />", " ").replace("・", " *"),
+                            desc: markup::to_text(&ele.leagueMatchSetting.leagueMatchEvent.desc),
+                            details: markup::to_text(&ele.leagueMatchSetting.leagueMatchEvent.regulation),
+                            regulation_url: ele.leagueMatchSetting.leagueMatchEvent.regulationUrl.clone().filter(|u| !u.is_empty()),
                         }
                     );
                 }
@@ -429,6 +481,7 @@ impl RmStructure {
                         id: "ChalSche".to_string(),
                         prev_sche: String::new(),
                         next_sche: String::new(),
+                        history: Vec::new(),
                         events: chal_schedule,
                     }
                 )
@@ -436,7 +489,6 @@ impl RmStructure {
         }
         //-----------------------------Salmon Run Schedule-----------------------------
         // if !schedule_data.data.coopGroupingSchedule..nodes.is_empty() {
-            active_ids.push("CoopSche".to_string());
             let mut coop_schedule = Vec::new();
             for ele in schedule_data.data.coopGroupingSchedule.regularSchedules.nodes.iter() {
                 coop_schedule.push(
@@ -445,6 +497,7 @@ impl RmStructure {
                         coop_setting: SalmonRunSetting {
                             coop_stage: Stage{
                                 name: ele.setting.coopStage.name.clone(),
+                                id: ele.setting.coopStage.id.clone(),
                                 image: Image{
                                     url: ele.setting.coopStage.image.url.clone()
                                 }
@@ -457,15 +510,16 @@ impl RmStructure {
                                             name: weapon.name.clone(),
                                             image: Image{
                                                 url: weapon.image.url.clone()
-                                            }
+                                            },
+                                            kind: WeaponKind::classify(&weapon.__splatoon3ink_id),
                                         }
                                     )
                                 }
                                 ret
                             },
-                            special: false
                         },
-                        king_guess: ele.__splatoon3ink_king_salmonid_guess.clone()
+                        kind: SalmonRunKind::Regular,
+                        king_guess: Some(ele.__splatoon3ink_king_salmonid_guess.clone())
                     }
                 )
             }
@@ -476,6 +530,7 @@ impl RmStructure {
                         coop_setting: SalmonRunSetting {
                             coop_stage: Stage{
                                 name: ele.setting.coopStage.name.clone(),
+                                id: ele.setting.coopStage.id.clone(),
                                 image: Image{
                                     url: ele.setting.coopStage.image.url.clone()
                                 }
@@ -488,15 +543,16 @@ impl RmStructure {
                                             name: weapon.name.clone(),
                                             image: Image{
                                                 url: weapon.image.url.clone()
-                                            }
+                                            },
+                                            kind: WeaponKind::classify(&weapon.__splatoon3ink_id),
                                         }
                                     )
                                 }
                                 ret
                             },
-                            special: true
                         },
-                        king_guess: ele.__splatoon3ink_king_salmonid_guess.clone()
+                        kind: SalmonRunKind::BigRun,
+                        king_guess: Some(ele.__splatoon3ink_king_salmonid_guess.clone())
                     }
                 )
             }
@@ -507,6 +563,7 @@ impl RmStructure {
                         coop_setting: SalmonRunSetting {
                             coop_stage: Stage{
                                 name: ele.setting.coopStage.name.clone(),
+                                id: ele.setting.coopStage.id.clone(),
                                 image: Image{
                                     url: ele.setting.coopStage.image.url.clone()
                                 }
@@ -519,19 +576,29 @@ impl RmStructure {
                                             name: weapon.name.clone(),
                                             image: Image{
                                                 url: weapon.image.url.clone()
-                                            }
+                                            },
+                                            kind: WeaponKind::classify(&weapon.__splatoon3ink_id),
                                         }
                                     )
                                 }
                                 ret
                             },
-                            special: true
                         },
-                        king_guess: "None".to_string()
+                        kind: SalmonRunKind::EggstraWork,
+                        king_guess: None
                     }
                 )
             }
-            coop_schedule.sort_by(|a, b| a.run_time.0.partial_cmp(&b.run_time.0).unwrap());
+            coop_schedule.sort_by_key(|e| e.run_time.0);
+            match coop_filter {
+                CoopFilter::All => {},
+                CoopFilter::Only(kind) => coop_schedule.retain(|e| e.kind == kind),
+                CoopFilter::PinBigRuns => {
+                    // Upcoming Big Runs go first, they're rare enough to plan around
+                    let now = chrono::Local::now();
+                    coop_schedule.sort_by_key(|e| !(e.kind == SalmonRunKind::BigRun && e.run_time.1 > now));
+                },
+            }
             active_schedules.push(
                 Box::new(
                     Schedule::<SalmonRunEvent> {
@@ -539,19 +606,31 @@ impl RmStructure {
                         id: "CoopSche".to_string(),
                         prev_sche: String::new(),
                         next_sche: String::new(),
+                        history: Vec::new(),
                         events: coop_schedule,
                     }
                 )
             );
         // }
-        for (i, sche) in active_schedules.iter_mut().enumerate() {
+        Self::link_schedules(&mut active_schedules);
+        active_schedules
+    }
+
+    // Points the arrows of every schedule at the ones around it, wrapping at the ends
+    pub fn link_schedules(schedules: &mut [Box<dyn Sche>]) {
+        let ids: Vec<String> = schedules.iter().map(|s| s.get_id().to_string()).collect();
+        for (i, sche) in schedules.iter_mut().enumerate() {
             sche.set_schedules(
-                active_ids[(i as isize - 1).rem_euclid(active_ids.len() as isize) as usize].clone(),
-                active_ids[(i as isize + 1).rem_euclid(active_ids.len() as isize) as usize].clone()
+                ids[(i as isize - 1).rem_euclid(ids.len() as isize) as usize].clone(),
+                ids[(i as isize + 1).rem_euclid(ids.len() as isize) as usize].clone()
             )
         }
+    }
+
+    pub fn generate(schedule_data: &crate::schedule_data::RotationData, splatfest_data: &crate::splatfest_data::region, releases: &crate::github_data::Releases, coop_filter: CoopFilter) -> Self {
+        let active_schedules = Self::generate_schedules(schedule_data, coop_filter);
 
-        let splatfest = match splatfest_data.US.data.festRecords.nodes.first() {
+        let splatfest = match splatfest_data.data.festRecords.nodes.first() {
                 Some(s) => {
                     if chrono::Local::now() < (s.endTime + chrono::Duration::days(1)) {
                         let teams = (
@@ -565,7 +644,9 @@ impl RmStructure {
                                 ).into(),
                                 image: Image{
                                     url: s.teams.0.image.url.clone()
-                                }
+                                },
+                                pre_votes: s.teams.0.preVotes.as_ref().map(|v| v.totalCount),
+                                votes: s.teams.0.votes.as_ref().map(|v| v.totalCount),
                             },
                             SplatfestTeam{
                                 name: s.teams.1.teamName.clone(),
@@ -577,7 +658,9 @@ impl RmStructure {
                                 ).into(),
                                 image: Image{
                                     url: s.teams.1.image.url.clone()
-                                }
+                                },
+                                pre_votes: s.teams.1.preVotes.as_ref().map(|v| v.totalCount),
+                                votes: s.teams.1.votes.as_ref().map(|v| v.totalCount),
                             },
                             SplatfestTeam{
                                 name: s.teams.2.teamName.clone(),
@@ -589,7 +672,9 @@ impl RmStructure {
                                 ).into(),
                                 image: Image{
                                     url: s.teams.2.image.url.clone()
-                                }
+                                },
+                                pre_votes: s.teams.2.preVotes.as_ref().map(|v| v.totalCount),
+                                votes: s.teams.2.votes.as_ref().map(|v| v.totalCount),
                             }
                         );
                         Some(
@@ -597,6 +682,7 @@ impl RmStructure {
                                 run_time: (s.startTime.clone(), s.endTime.clone()),
                                 title: s.title.clone(),
                                 teams: teams.clone(),
+                                undecided_votes: s.undecidedVotes.as_ref().map(|v| v.totalCount),
                                 state: {
                                     let mut ret = SplatfestState::MissingTricolor;
                                     if let Some(f) = &schedule_data.data.currentFest {
@@ -604,6 +690,7 @@ impl RmStructure {
                                             f.midtermTime.clone(),
                                             Stage{
                                                 name: f.tricolorStage.name.clone(),
+                                                id: f.tricolorStage.id.clone(),
                                                 image: Image{
                                                     url: f.tricolorStage.image.url.clone()
                                                 }
@@ -631,21 +718,21 @@ impl RmStructure {
                                                                 votes:      (r0.isVoteRatioTop,                     r0.voteRatio),
                                                                 open:       (r0.isRegularContributionRatioTop,      r0.regularContributionRatio),
                                                                 pro:        (r0.isChallengeContributionRatioTop,    r0.challengeContributionRatio),
-                                                                tricolor:   (r0.isTricolorContributionRatioTop.unwrap_or(false),     r0.tricolorContributionRatio.unwrap_or(0.0)),
+                                                                tricolor:   r0.tricolorContributionRatio.map(|r| (r0.isTricolorContributionRatioTop.unwrap_or(false), r)),
                                                             },
                                                             SplatfestTeamResult{
                                                                 sneak_peak: (r1.isHoragaiRatioTop,                  r1.horagaiRatio),
                                                                 votes:      (r1.isVoteRatioTop,                     r1.voteRatio),
                                                                 open:       (r1.isRegularContributionRatioTop,      r1.regularContributionRatio),
                                                                 pro:        (r1.isChallengeContributionRatioTop,    r1.challengeContributionRatio),
-                                                                tricolor:   (r1.isTricolorContributionRatioTop.unwrap_or(false),     r1.tricolorContributionRatio.unwrap_or(0.0)),
+                                                                tricolor:   r1.tricolorContributionRatio.map(|r| (r1.isTricolorContributionRatioTop.unwrap_or(false), r)),
                                                             },
                                                             SplatfestTeamResult{
                                                                 sneak_peak: (r2.isHoragaiRatioTop,                  r2.horagaiRatio),
                                                                 votes:      (r2.isVoteRatioTop,                     r2.voteRatio),
                                                                 open:       (r2.isRegularContributionRatioTop,      r2.regularContributionRatio),
                                                                 pro:        (r2.isChallengeContributionRatioTop,    r2.challengeContributionRatio),
-                                                                tricolor:   (r2.isTricolorContributionRatioTop.unwrap_or(false),     r2.tricolorContributionRatio.unwrap_or(0.0)),
+                                                                tricolor:   r2.tricolorContributionRatio.map(|r| (r2.isTricolorContributionRatioTop.unwrap_or(false), r)),
                                                             }
                                                         )
                                                     }
@@ -679,37 +766,66 @@ impl RmStructure {
     }
 }
 impl ToRM for RmStructure {
-    fn to_rm(&self) -> Vec<RmObject> {
-        let mut ret = {
-            let mut ret = Vec::new();
-            for ele in self.schedules.iter() {
-                ret.append(&mut ele.to_rm());
-            }
-            ret
-        };
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
+        let mut layout = Container::column().align(Align::Center);
+        if let Some(notif) = &self.update_notif {
+            layout = layout.push(notif.to_rm(style));
+        }
         if let Some(splatfest) = &self.splatfest {
-            for obj in ret.iter_mut() {
-                if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                    o.pos += (0,
-                        match splatfest.state {
-                            SplatfestState::MissingTricolor | SplatfestState::Active(_, _) => 150,
-                            SplatfestState::Finished(_) => 400,
-                        }
-                    ).into();
-                }
-            }
-            ret.append(&mut splatfest.to_rm());
+            layout = layout.push(splatfest.to_rm(style));
         }
-        if let Some(notif) = &self.update_notif {
-            for obj in ret.iter_mut() {
-                if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                    o.pos += (0,50).into();
+        let schedules = if style.columns.is_empty() {
+            Container::stack().align(Align::Center)
+                .push_all(self.schedules.iter().map(|s| s.to_rm(style)))
+                .build()
+        } else {
+            Container::row()
+                .push_all(style.columns.iter().filter_map(|id| self.schedules.iter().find(|s| s.get_id() == id)).map(|s| s.to_rm(style)))
+                .build()
+        };
+        let mut layout = layout.push(schedules).build();
+        layout.scale(style.scale);
+        let mut objects = layout.into_objects();
+        if style.output == OutputMode::Variables {
+            let variables = extract_variables(&mut objects);
+            objects.insert(0, RmObject::new(ObjectType::Variables(variables)).prefix_name_owned("Variables"));
+        }
+        objects
+    }
+}
+
+// Moves the values that change between rotations (texts, images, tooltips and time
+// bar times) into variables so they can be updated with !SetVariable without a refresh
+fn extract_variables(objects: &mut [RmObject]) -> Vec<(String, String)> {
+    let mut ret = Vec::new();
+    for obj in objects.iter_mut() {
+        let name = obj.name.clone();
+        let mut extract = |suffix: &str, value: &mut String| {
+            let var = format!("{name}{suffix}");
+            ret.push((var.clone(), std::mem::replace(value, format!("#{var}#"))));
+        };
+        match obj.object_type {
+            ObjectType::Meter(ref mut t, ref mut o) => {
+                match t {
+                    MeterType::String(s) if !s.text.is_empty() => extract("Text", &mut s.text),
+                    MeterType::Image(i) if !i.image_name.is_empty() => extract("Image", &mut i.image_name),
+                    _ => {},
                 }
-            }
-            ret.append(&mut notif.to_rm());
+                if let Some(tool_tip) = &mut o.tool_tip {
+                    extract("ToolTip", &mut tool_tip.tool_tip_text);
+                }
+                o.dynamic_variables = true;
+            },
+            ObjectType::Measure(MeasureType::Plugin(PluginType::Splatink(SplatinkType::TimeBar(ref mut t))), ref mut o) => {
+                ret.push((format!("{name}Start"), t.start_time.to_string()));
+                ret.push((format!("{name}End"), t.end_time.to_string()));
+                t.variable = Some(name);
+                o.dynamic_variables = true;
+            },
+            _ => {},
         }
-        ret
     }
+    ret
 }
 impl Download for RmStructure {
     fn download(&self, dir_path: &str) -> Vec<Result<(), String>> {
@@ -726,187 +842,311 @@ impl Download for RmStructure {
 
 pub trait Sche: ToRM + Download {
     fn set_schedules(&mut self, prev: String, next: String);
+    fn set_history(&mut self, history: Vec<HistoryEntry>);
     fn get_id(&self) -> &str;
+    fn get_title(&self) -> &str;
+    // The `index`th event that hasn't ended yet, 0 being the current one
+    fn event(&self, index: usize) -> Option<&dyn Query>;
+}
+
+// Lookups behind the plugin's section variable functions, e.g. Current(RegSche, Stage1)
+pub trait Query {
+    // Run time that is on now or next up
+    fn run_time(&self) -> Option<(DateTime<Local>, DateTime<Local>)>;
+    fn query(&self, field: &str) -> Option<String>;
+    // One line description, used for exports
+    fn summary(&self) -> String;
+    // Battle settings of the event, for the schedules that have them
+    fn vs_setting(&self) -> Option<&VsSetting> {
+        None
+    }
+    fn query_time(&self, field: &str) -> Option<String> {
+        let (start, end) = self.run_time()?;
+        match field.to_lowercase().as_str() {
+            "start" => Some(start.format(DISPLAY_TIME_FORMAT).to_string()),
+            "end" => Some(end.format(DISPLAY_TIME_FORMAT).to_string()),
+            "starttimestamp" => Some(start.timestamp().to_string()),
+            "endtimestamp" => Some(end.timestamp().to_string()),
+            _ => self.query(field),
+        }
+    }
+}
+
+// "1h 23m" style countdown, days are only shown when there are any
+pub fn format_duration(duration: chrono::Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    match (minutes / (60 * 24), minutes / 60 % 24, minutes % 60) {
+        (0, 0, m) => format!("{m}m"),
+        (0, h, m) => format!("{h}h {m}m"),
+        (d, h, m) => format!("{d}d {h}h {m}m"),
+    }
 }
 
-pub struct Schedule<T: ToRM + Download> {
+pub struct Schedule<T: ToRM + Download + Query> {
     pub title: String,
     pub id: String,
     pub prev_sche: String,
     pub next_sche: String,
     pub events: Vec<T>,
+    // Rotations that are over, the most recent first, shown greyed out above the events
+    pub history: Vec<HistoryEntry>,
 }
-impl <T: ToRM + Download> Sche for Schedule<T> {
+impl <T: ToRM + Download + Query> Sche for Schedule<T> {
     fn set_schedules(&mut self, prev: String, next: String) {
         self.prev_sche = prev;
         self.next_sche = next;
     }
+    fn set_history(&mut self, history: Vec<HistoryEntry>) {
+        self.history = history;
+    }
     fn get_id(&self) -> &str {
         &self.id
     }
+    fn get_title(&self) -> &str {
+        &self.title
+    }
+    fn event(&self, index: usize) -> Option<&dyn Query> {
+        let now = chrono::Local::now();
+        self.events.iter()
+            .filter(|e| e.run_time().is_some_and(|(_, end)| end > now))
+            .nth(index)
+            .map(|e| e as &dyn Query)
+    }
 }
-impl <T: ToRM + Download> Download for Schedule<T> {
+impl <T: ToRM + Download + Query> Download for Schedule<T> {
     fn download(&self, dir_path: &str) -> Vec<Result<(), String>> {
         self.events.download(dir_path)
     }
 }
-impl <T: ToRM + Download> ToRM for Schedule<T> {
-    fn to_rm(&self) -> Vec<RmObject> {
-        let mut ret = Vec::new();
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::Image(
-                        ImageOptions{
-                            image_name: format!("#@#Schedule Types/{}.png", self.prev_sche),
-                            preseve_aspect_ratio: false,
-                        }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (0+50,0).into();
-                        ret.size = (50,50).into();
-                        ret.solid_color = Some((50,50,50,255).into());
-                        ret.left_click_action.push(format!("!CommandMeasure SplatinkCore \"redrawsche {}\"", self.prev_sche));
-                        ret
-                    }
-                )
-            ).prefix_name_owned("PrevScheImage")
-        );
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::String(
-                        {
-                            let mut ret = StringOptions::default();
-                            ret.text = "<<".to_string();
-                            ret
-                        }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (75+50,25).into();
-                        ret.size = (50,50).into();
-                        ret.solid_color = Some((50,50,50,255).into());
-                        ret.left_click_action.push(format!("!CommandMeasure SplatinkCore \"redrawsche {}\"", self.prev_sche));
-                        ret
-                    }
-                )
-            ).prefix_name_owned("PrevScheArrow")
-        );
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::Image(
-                        ImageOptions{
-                            image_name: format!("#@#Schedule Types/{}.png", self.id),
-                            preseve_aspect_ratio: false,
-                        }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (100+50,0).into();
-                        ret.size = (50,50).into();
-                        ret.solid_color = Some((40,40,40,255).into());
-                        ret
-                    }
+impl <T: ToRM + Download + Query> Schedule<T> {
+    // Line under the header saying when a favorite rotation is on next. Schedules
+    // without battle settings don't get one
+    fn next_favorite(&self, style: &Style) -> Option<String> {
+        if style.favorites.is_empty() || !self.events.iter().any(|e| e.vs_setting().is_some()) {
+            return None;
+        }
+        let now = chrono::Local::now();
+        let next = self.events.iter()
+            .filter(|e| e.run_time().is_some_and(|(_, end)| end > now))
+            .find(|e| e.vs_setting().is_some_and(|s| style.favorites.matches(s)));
+        Some(match next.and_then(|e| Some((e.run_time()?, e.summary()))) {
+            Some(((start, _), summary)) if start <= now => format!("Favorite on now: {summary}"),
+            Some(((start, _), summary)) => format!("Next favorite {}: {summary}", start.format(DISPLAY_TIME_FORMAT)),
+            None => "No favorites coming up".to_string(),
+        })
+    }
+}
+impl <T: ToRM + Download + Query> ToRM for Schedule<T> {
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
+        let column = style.is_column(&self.id);
+        let mut header = Container::row();
+        if !column {
+            header = header
+                .push(
+                    RmObject::new(
+                        ObjectType::meter(
+                            MeterType::Image(
+                                ImageOptions{
+                                    image_name: format!("#@#Schedule Types/{}.png", self.prev_sche),
+                                    ..ImageOptions::default()
+                                }
+                            ),
+                            {
+                                let mut ret = MeterOptions::new();
+                                ret.size = (50,50).into();
+                                ret.solid_color = Some(style.theme.background.clone());
+                                ret.left_click_action.push(style.command(&format!("redrawsche {}", self.prev_sche)));
+                                ret
+                            }
+                        )
+                    ).prefix_name_owned("PrevScheImage")
                 )
-            ).prefix_name_owned("CurrScheImage")
-        );
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::String(
+                .push(
+                    RmObject::new(
+                        ObjectType::meter(
+                            MeterType::String(
+                                {
+                                    let mut ret = style.theme.string_options();
+                                    ret.text = "<<".to_string();
+                                    ret
+                                }
+                            ),
+                            {
+                                let mut ret = MeterOptions::new();
+                                ret.pos = (25,25).into();
+                                ret.size = (50,50).into();
+                                ret.solid_color = Some(style.theme.background.clone());
+                                ret.left_click_action.push(style.command(&format!("redrawsche {}", self.prev_sche)));
+                                ret
+                            }
+                        )
+                    ).prefix_name_owned("PrevScheArrow")
+                );
+        }
+        header = header
+            .push(
+                RmObject::new(
+                    ObjectType::meter(
+                        MeterType::Image(
+                            ImageOptions{
+                                image_name: format!("#@#Schedule Types/{}.png", self.id),
+                                ..ImageOptions::default()
+                            }
+                        ),
                         {
-                            let mut ret = StringOptions::default();
-                            ret.text = self.title.clone();
+                            let mut ret = MeterOptions::new();
+                            ret.size = (50,50).into();
+                            ret.solid_color = Some(style.theme.panel.clone());
                             ret
                         }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (200+50,25).into();
-                        ret.size = (100,50).into();
-                        ret.solid_color = Some((40,40,40,255).into());
-                        ret
-                    }
-                )
-            ).prefix_name_owned("CurrScheTitle")
-        );
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::String(
+                    )
+                ).prefix_name_owned("CurrScheImage")
+            )
+            .push(
+                RmObject::new(
+                    ObjectType::meter(
+                        MeterType::String(
+                            {
+                                let mut ret = style.theme.string_options();
+                                ret.text = self.title.clone();
+                                ret
+                            }
+                        ),
                         {
-                            let mut ret = StringOptions::default();
-                            ret.text = ">>".to_string();
+                            let mut ret = MeterOptions::new();
+                            ret.pos = (50,25).into();
+                            ret.size = (100,50).into();
+                            ret.solid_color = Some(style.theme.panel.clone());
                             ret
                         }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (275+50,25).into();
-                        ret.size = (50,50).into();
-                        ret.solid_color = Some((50,50,50,255).into());
-                        ret.left_click_action.push(format!("!CommandMeasure SplatinkCore \"redrawsche {}\"", self.next_sche));
-                        ret
-                    }
+                    )
+                ).prefix_name_owned("CurrScheTitle")
+            );
+        if !column {
+            header = header
+                .push(
+                    RmObject::new(
+                        ObjectType::meter(
+                            MeterType::String(
+                                {
+                                    let mut ret = style.theme.string_options();
+                                    ret.text = ">>".to_string();
+                                    ret
+                                }
+                            ),
+                            {
+                                let mut ret = MeterOptions::new();
+                                ret.pos = (25,25).into();
+                                ret.size = (50,50).into();
+                                ret.solid_color = Some(style.theme.background.clone());
+                                ret.left_click_action.push(style.command(&format!("redrawsche {}", self.next_sche)));
+                                ret
+                            }
+                        )
+                    ).prefix_name_owned("NextScheArrow")
                 )
-            ).prefix_name_owned("NextScheArrow")
-        );
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::Image(
-                        ImageOptions{
-                            image_name: format!("#@#Schedule Types/{}.png", self.next_sche),
-                            preseve_aspect_ratio: false,
-                        }
-                    ),
+                .push(
+                    RmObject::new(
+                        ObjectType::meter(
+                            MeterType::Image(
+                                ImageOptions{
+                                    image_name: format!("#@#Schedule Types/{}.png", self.next_sche),
+                                    ..ImageOptions::default()
+                                }
+                            ),
+                            {
+                                let mut ret = MeterOptions::new();
+                                ret.size = (50,50).into();
+                                ret.solid_color = Some(style.theme.background.clone());
+                                ret.left_click_action.push(style.command(&format!("redrawsche {}", self.next_sche)));
+                                ret
+                            }
+                        )
+                    ).prefix_name_owned("NextScheImage")
+                );
+        }
+
+        let header = header.build();
+        let favorite = self.next_favorite(style).map(|text| RmObject::new(ObjectType::meter(
+            MeterType::String(
+                {
+                    let mut ret = style.theme.small_string_options();
+                    ret.text = text;
+                    ret
+                }
+            ),
+            {
+                let mut ret = MeterOptions::new();
+                ret.pos = (header.size.x / 2, 12).into();
+                ret.size = (header.size.x, 25).into();
+                ret.solid_color = Some(style.theme.panel.clone());
+                ret
+            }
+        )).prefix_name_owned("NextFavorite"));
+        let history = self.history.iter().enumerate().map(|(i, entry)| {
+            let size: Coord = (header.size.x, 25).into();
+            let text = RmObject::new(ObjectType::meter(
+                MeterType::String(
                     {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (300+50,0).into();
-                        ret.size = (50,50).into();
-                        ret.solid_color = Some((50,50,50,255).into());
-                        ret.left_click_action.push(format!("!CommandMeasure SplatinkCore \"redrawsche {}\"", self.next_sche));
+                        let mut ret = style.theme.small_string_options();
+                        ret.text = match entry.run_time() {
+                            Some((start, _)) => format!("{}: {}", start.format(DISPLAY_TIME_FORMAT), entry.summary),
+                            None => entry.summary.clone(),
+                        };
                         ret
                     }
-                )
-            ).prefix_name_owned("NextScheImage")
-        );
-
-
-        ret.append(&mut {
-            let mut ret = Vec::new();
-            let mut vert_size_accum = 50;
-            for (i, ele) in self.events.iter().enumerate() {
-                let mut vert_size = 50;
-                ret.append(&mut {
-                    let mut ret = ele.to_rm();
-                    for obj in ret.iter_mut() {
-                        if let ObjectType::Meter(ref t, ref mut o) = obj.prefix_name_mut(&format!("{i}")).object_type {
-                            vert_size = match t {
-                                MeterType::String(s) if s.string_align == Some(crate::rm_write::StringAlign::CenterCenter) => vert_size.max(o.pos.y + o.size.y / 2),
-                                _ => vert_size.max(o.pos.y + o.size.y),
-                            };
-                            o.pos += (((self.id.contains("Chal") || self.id.contains("Coop")).not().then_some(50).unwrap_or_default()), vert_size_accum).into();
-                        }
+                ),
+                {
+                    let mut ret = MeterOptions::new();
+                    ret.pos = (size.x / 2, size.y / 2).into();
+                    ret.size = size.clone();
+                    ret.solid_color = Some(style.theme.panel.clone());
+                    if let Some((start, end)) = entry.run_time() {
+                        ret.tool_tip = Some(ToolTip::new(format!("{} - {}", start.format(TOOLTIP_TIME_FORMAT), end.format(TOOLTIP_TIME_FORMAT))));
                     }
                     ret
-                });
-                vert_size_accum += vert_size;
+                }
+            )).prefix_name_owned("Text");
+            let mut ret = Container::stack()
+                .push(text)
+                .push(new_shade(size).prefix_name_owned("Shade"))
+                .build();
+            for obj in ret.objects.iter_mut() {
+                obj.prefix_name_mut(&format!("Previous{i}"));
+            }
+            ret
+        }).collect::<Vec<_>>();
+
+        let events = self.events.iter().take(style.layout.event_limit()).enumerate().map(|(i, ele)| {
+            let mut ret = ele.to_rm(style);
+            for obj in ret.iter_mut() {
+                obj.prefix_name_mut(&format!("{i}"));
             }
             ret
         });
+        let events = match style.orientation {
+            Orientation::Vertical => Container::column().align(Align::Center).push_all(events).build(),
+            Orientation::Horizontal => Container::row().push_all(events).build(),
+        };
+
+        let mut ret = Container::column().align(Align::Center)
+            .push(header)
+            .push_all(favorite)
+            .push_all(history)
+            .push(events)
+            .build()
+            .into_objects();
 
         for obj in ret.iter_mut() {
             obj.prefix_name_mut(&self.id);
             if let ObjectType::Meter(_, ref mut o) = obj.object_type {
                 o.groups.push(self.id.clone());
-                o.scroll_down_action.push(format!("!CommandMeasure SplatinkCore \"redrawsche {}\"", self.next_sche));
-                o.scroll_up_action.push(format!("!CommandMeasure SplatinkCore \"redrawsche {}\"", self.prev_sche));
-                if self.id != "RegSche" {
-                    o.hidden = true;
+                if !column {
+                    o.scroll_down_action.push(style.command(&format!("redrawsche {}", self.next_sche)));
+                    o.scroll_up_action.push(style.command(&format!("redrawsche {}", self.prev_sche)));
+                    if self.id != "RegSche" {
+                        o.hidden = true;
+                    }
                 }
             }
         }
@@ -919,23 +1159,26 @@ pub struct VsEvent {
     pub vs_setting: VsSetting,
 }
 impl ToRM for VsEvent {
-    fn to_rm(&self) -> Vec<RmObject> {
-        let mut ret = Vec::new();
-        ret.append(&mut {
-            let mut ret = self.run_time.to_rm();
-
-            ret
-        });
-        ret.append(&mut {
-            let mut ret = self.vs_setting.to_rm();
-            for obj in ret.iter_mut() {
-                if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                    o.pos += (100, 0).into();
-                }
-            }
-            ret
-        });
-        ret
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
+        Container::row()
+            .push(self.run_time.to_rm(style))
+            .push(self.vs_setting.to_rm(style))
+            .build()
+            .into_objects()
+    }
+}
+impl Query for VsEvent {
+    fn run_time(&self) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        Some(self.run_time)
+    }
+    fn query(&self, field: &str) -> Option<String> {
+        self.vs_setting.query(field)
+    }
+    fn summary(&self) -> String {
+        self.vs_setting.summary()
+    }
+    fn vs_setting(&self) -> Option<&VsSetting> {
+        Some(&self.vs_setting)
     }
 }
 impl Download for VsEvent {
@@ -949,33 +1192,30 @@ pub struct VsSetting {
     pub vs_stages: (Stage, Stage),
 }
 impl ToRM for VsSetting {
-    fn to_rm(&self) -> Vec<RmObject> {
-        let mut ret = Vec::new();
-        ret.push({
-            let mut ret = self.vs_rule.get_rm_object();
-            ret.prefix_name_mut("Mode");
-            if let ObjectType::Meter(_, ref mut o) = ret.object_type {
-                o.pos += (0,0).into();
-            }
-            ret
-        });
-        ret.push({
-            let mut ret = self.vs_stages.0.get_rm_object();
-            ret.prefix_name_mut("Stage0");
-            if let ObjectType::Meter(_, ref mut o) = ret.object_type {
-                o.pos += (50,0).into();
-            }
-            ret
-        });
-        ret.push({
-            let mut ret = self.vs_stages.1.get_rm_object();
-            ret.prefix_name_mut("Stage1");
-            if let ObjectType::Meter(_, ref mut o) = ret.object_type {
-                o.pos += (150,0).into();
-            }
-            ret
-        });
-        ret
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
+        let row = Container::row()
+            .push(self.vs_rule.get_rm_object(style).prefix_name_owned("Mode"))
+            .push(self.vs_stages.0.get_rm_objects(style, "Stage0"))
+            .push(self.vs_stages.1.get_rm_objects(style, "Stage1"))
+            .build();
+        if !style.favorites.matches(self) {
+            return row.into_objects();
+        }
+        let border = new_outline(row.size.clone(), style.theme.favorite.clone()).prefix_name_owned("Favorite");
+        Container::stack().push(row).push(border).build().into_objects()
+    }
+}
+impl VsSetting {
+    fn query(&self, field: &str) -> Option<String> {
+        match field.to_lowercase().as_str() {
+            "rule" | "mode" => Some(self.vs_rule.name.clone()),
+            "stage1" => Some(self.vs_stages.0.name.clone()),
+            "stage2" => Some(self.vs_stages.1.name.clone()),
+            _ => None,
+        }
+    }
+    fn summary(&self) -> String {
+        format!("{} on {} and {}", self.vs_rule.name, self.vs_stages.0.name, self.vs_stages.1.name)
     }
 }
 impl Download for VsSetting {
@@ -991,19 +1231,19 @@ pub struct VsRule {
     pub name: String,
 }
 impl VsRule {
-    pub fn get_rm_object(&self) -> RmObject {
+    pub fn get_rm_object(&self, style: &Style) -> RmObject {
         RmObject::new(
-            ObjectType::Meter(
+            ObjectType::meter(
                 MeterType::Image(
                     ImageOptions{
                         image_name: format!("#@#Modes/{}.png", self.name),
-                        preseve_aspect_ratio: false,
+                        ..ImageOptions::default()
                     }
                 ),
                 {
                     let mut ret = MeterOptions::new();
                     ret.size = (50,50).into();
-                    ret.solid_color = Some((40,40,40,255).into());
+                    ret.solid_color = Some(style.theme.panel.clone());
                     ret.tool_tip = Some(ToolTip::new(self.name.clone()));
                     ret
                 }
@@ -1014,22 +1254,23 @@ impl VsRule {
 
 pub struct Stage {
     pub name: String,
+    pub id: String,
     pub image: Image,
 }
 impl Stage {
-    pub fn get_rm_object(&self) -> RmObject {
+    pub fn get_rm_object(&self, style: &Style) -> RmObject {
         RmObject::new(
-            ObjectType::Meter(
+            ObjectType::meter(
                 MeterType::Image(
                     ImageOptions{
                         image_name: format!("#@#Stages/{}.png", self.name),
-                        preseve_aspect_ratio: false,
+                        ..ImageOptions::default()
                     }
                 ),
                 {
                     let mut ret = MeterOptions::new();
                     ret.size = (100,50).into();
-                    ret.solid_color = Some((30,30,30,255).into());
+                    ret.solid_color = Some(style.theme.cell.clone());
                     ret.tool_tip = Some(ToolTip::new(self.name.clone()));
                     ret
                 }
@@ -1037,8 +1278,41 @@ impl Stage {
         )
     }
 }
-impl Download for Stage {
-    fn download(&self, dir_path: &str) -> Vec<Result<(), String>> {
+impl Stage {
+    // The stage image, with its name written underneath in the expanded layout
+    pub fn get_rm_objects(&self, style: &Style, prefix: &str) -> Vec<RmObject> {
+        let image = self.get_rm_object(style).prefix_name_owned(prefix);
+        if style.layout != LayoutMode::Expanded {
+            return vec![image];
+        }
+        Container::column()
+            .push(image)
+            .push(
+                RmObject::new(
+                    ObjectType::meter(
+                        MeterType::String(
+                            {
+                                let mut ret = style.theme.small_string_options();
+                                ret.text = self.name.clone();
+                                ret
+                            }
+                        ),
+                        {
+                            let mut ret = MeterOptions::new();
+                            ret.pos = (50,10).into();
+                            ret.size = (100,20).into();
+                            ret.solid_color = Some(style.theme.panel.clone());
+                            ret
+                        }
+                    )
+                ).prefix_name_owned(&format!("{prefix}Label"))
+            )
+            .build()
+            .into_objects()
+    }
+}
+impl Download for Stage {
+    fn download(&self, dir_path: &str) -> Vec<Result<(), String>> {
         vec![self.image.download(&self.name, &format!("{dir_path}/Stages"))]
     }
 }
@@ -1049,146 +1323,371 @@ pub struct ChalEvent {
     pub title: String,
     pub desc: String,
     pub details: String,
+    pub regulation_url: Option<String>,
 }
-impl ToRM for ChalEvent {
-    fn to_rm(&self) -> Vec<RmObject> {
+impl ChalEvent {
+    // Periods that are over get faded out and the one that's on gets outlined
+    fn period_to_rm(period: &(DateTime<Local>, DateTime<Local>), style: &Style) -> Block {
+        let now = chrono::Local::now();
+        let times = Block::new(period.to_rm(style));
+        let size = times.size.clone();
+        let state = if period.1 <= now {
+            Some(new_shade(size))
+        } else if period.0 <= now {
+            Some(new_outline(size, style.theme.highlight_text.clone()))
+        } else {
+            None
+        };
+        Container::stack()
+            .push(times)
+            .push_all(state.map(|s| s.prefix_name_owned("State")))
+            .build()
+    }
+    // The full regulation laid over the event, hidden until the title or description is clicked
+    fn details_to_rm(&self, size: Coord, style: &Style) -> Vec<RmObject> {
+        let group = format!("Details{}", self.run_time.first().map_or(0, |(start, _)| start.timestamp()));
+        let toggle = [format!("!ToggleMeterGroup {group}"), "!Redraw".to_string()];
         let mut ret = Vec::new();
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::Image(
-                        ImageOptions{
-                            image_name: String::new(),
-                            preseve_aspect_ratio: false,
-                        }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.size = (200,150).into();
-                        ret.solid_color = Some((30,30,30,255).into());
-                        ret
-                    }
-                )
-            ).prefix_name_owned("Background")
-        );
-        for (i, ele) in self.run_time.iter().enumerate() {
-            ret.append(&mut {
-                let mut ret = ele.to_rm();
-                for obj in ret.iter_mut() {
-                    obj.prefix_name_mut(&format!("Time{i}"));
-                    if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                        let vert_height: usize = if self.run_time.len() > 3 {(self.run_time.len() as f64 / 2.0).ceil() as usize} else {self.run_time.len()};
-                        o.pos += (if self.run_time.len() > 3 {if i >= vert_height {100} else {0}} else {50},(75 - vert_height * 25 + 50 * (i % vert_height) + if i >= vert_height && self.run_time.len() % 2 == 1 {25} else {0}) as isize).into();
-                    }
-                }
-                ret
-            });
-        }
-        ret.append(&mut {
-            let mut ret = self.vs_setting.to_rm();
-            for obj in ret.iter_mut() {
-                if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                    o.pos += (200,0).into();
-                }
+        ret.push({
+            let mut panel = new_panel(size.clone(), style.theme.panel.clone()).prefix_name_owned("Details");
+            if let ObjectType::Meter(_, ref mut o) = panel.object_type {
+                o.left_click_action.extend(toggle.iter().cloned());
             }
-            ret
+            panel
         });
-        ret.push({
-            RmObject::new(ObjectType::Meter(
-                MeterType::String(
-                    {
-                        let mut ret = StringOptions::default();
-                        ret.text = self.title.clone();
-                        ret
-                    }
-                ),
+        let link_height = if self.regulation_url.is_some() {25} else {0};
+        ret.push(RmObject::new(ObjectType::meter(
+            MeterType::String(
                 {
-                    let mut ret = MeterOptions::new();
-                    ret.pos = (325,75).into();
-                    ret.size = (250,50).into();
-                    ret.solid_color = Some((40,40,40,255).into());
-                    ret.tool_tip = Some(ToolTip::new(self.details.clone()));
+                    let mut ret = style.theme.small_string_options();
+                    ret.text = markup::to_skin_text(&self.details);
+                    ret.clip_string = Some(2);
                     ret
                 }
-            ))
-        }.prefix_name_owned("Title"));
-        ret.push({
-            RmObject::new(ObjectType::Meter(
+            ),
+            {
+                let mut ret = MeterOptions::new();
+                ret.pos = (10,10).into();
+                ret.size = (size.x - 20, size.y - 20 - link_height).into();
+                ret.left_click_action.extend(toggle.iter().cloned());
+                ret
+            }
+        )).prefix_name_owned("DetailsText"));
+        if let Some(url) = &self.regulation_url {
+            ret.push(RmObject::new(ObjectType::meter(
                 MeterType::String(
                     {
-                        let mut ret = StringOptions::default();
-                        ret.text = self.desc.clone();
-                        ret.font_size = Some(10_f64);
-                        ret.font_weight = Some(400);
+                        let mut ret = style.theme.small_string_options();
+                        ret.text = "Open Regulation".to_string();
+                        ret.font_color = Some(style.theme.highlight_text.clone());
                         ret
                     }
                 ),
                 {
                     let mut ret = MeterOptions::new();
-                    ret.pos = (325,125).into();
-                    ret.size = (250,50).into();
-                    ret.solid_color = Some((40,40,40,255).into());
-                    ret.tool_tip = Some(ToolTip::new(self.details.clone()));
+                    ret.pos = (10, size.y - 10 - link_height).into();
+                    ret.size = (size.x - 20, link_height).into();
+                    ret.left_click_action.push(format!("\"{url}\""));
+                    ret.tool_tip = Some(ToolTip::new(url.clone()));
                     ret
                 }
-            ))
-        }.prefix_name_owned("Desc"));
+            )).prefix_name_owned("DetailsLink"));
+        }
+        for obj in ret.iter_mut() {
+            if let ObjectType::Meter(_, ref mut o) = obj.object_type {
+                o.groups.push("Details".to_string());
+                o.groups.push(group.clone());
+                o.hidden = true;
+            }
+        }
         ret
     }
 }
+impl ToRM for ChalEvent {
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
+        let times = {
+            let vert_height = if self.run_time.len() > 3 {(self.run_time.len() as f64 / 2.0).ceil() as usize} else {self.run_time.len()};
+            let mut columns = Vec::new();
+            for (c, chunk) in self.run_time.chunks(vert_height.max(1)).enumerate() {
+                columns.push(
+                    Container::column()
+                        .push_all(chunk.iter().enumerate().map(|(i, ele)| {
+                            let mut ret = ChalEvent::period_to_rm(ele, style);
+                            for obj in ret.objects.iter_mut() {
+                                obj.prefix_name_mut(&format!("Time{}", c * vert_height + i));
+                            }
+                            ret
+                        }))
+                        .build()
+                );
+            }
+            Container::row().align(Align::Center).push_all(columns).build()
+        };
+        let toggle = format!("!ToggleMeterGroup Details{}", self.run_time.first().map_or(0, |(start, _)| start.timestamp()));
+        let tool_tip = markup::to_skin_text(&self.details);
+        let background = new_panel((200,150).into(), style.theme.cell.clone()).prefix_name_owned("Background");
+        let title = RmObject::new(ObjectType::meter(
+            MeterType::String(
+                {
+                    let mut ret = style.theme.string_options();
+                    ret.text = self.title.clone();
+                    ret
+                }
+            ),
+            {
+                let mut ret = MeterOptions::new();
+                ret.pos = (125,25).into();
+                ret.size = (250,50).into();
+                ret.solid_color = Some(style.theme.panel.clone());
+                ret.tool_tip = Some(ToolTip::new(tool_tip.clone()));
+                ret.left_click_action.push(toggle.clone());
+                ret.left_click_action.push("!Redraw".to_string());
+                ret
+            }
+        )).prefix_name_owned("Title");
+        let desc = RmObject::new(ObjectType::meter(
+            MeterType::String(
+                {
+                    let mut ret = style.theme.small_string_options();
+                    ret.text = self.desc.replace('\n', " ");
+                    ret
+                }
+            ),
+            {
+                let mut ret = MeterOptions::new();
+                ret.pos = (125,25).into();
+                ret.size = (250,50).into();
+                ret.solid_color = Some(style.theme.panel.clone());
+                ret.tool_tip = Some(ToolTip::new(tool_tip));
+                ret.left_click_action.push(toggle);
+                ret.left_click_action.push("!Redraw".to_string());
+                ret
+            }
+        )).prefix_name_owned("Desc");
+
+        let event = Container::row()
+            .push(Container::stack().align(Align::Center).push(background).push(times).build())
+            .push(
+                Container::column()
+                    .push(self.vs_setting.to_rm(style))
+                    .push(title)
+                    .push(desc)
+                    .build()
+            )
+            .build();
+        let event_size = event.size.clone();
+        let details = self.details_to_rm(event_size.clone(), style);
+        Container::stack()
+            .push(event)
+            .push(Block::sized(details, event_size))
+            .build()
+            .into_objects()
+    }
+}
+impl Query for ChalEvent {
+    fn run_time(&self) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        let now = chrono::Local::now();
+        self.run_time.iter().find(|(_, end)| *end > now).or(self.run_time.last()).copied()
+    }
+    fn query(&self, field: &str) -> Option<String> {
+        match field.to_lowercase().as_str() {
+            "title" => Some(self.title.clone()),
+            "desc" => Some(self.desc.clone()),
+            "details" => Some(self.details.clone()),
+            "url" => self.regulation_url.clone(),
+            _ => self.vs_setting.query(field),
+        }
+    }
+    fn summary(&self) -> String {
+        format!("{}: {}", self.title, self.vs_setting.summary())
+    }
+    fn vs_setting(&self) -> Option<&VsSetting> {
+        Some(&self.vs_setting)
+    }
+}
 impl Download for ChalEvent {
     fn download(&self, dir_path: &str) -> Vec<Result<(), String>> {
         self.vs_setting.download(dir_path)
     }
 }
 
-pub struct SalmonRunEvent {
-    pub run_time: (DateTime<Local>, DateTime<Local>),
-    pub coop_setting: SalmonRunSetting,
-    pub king_guess: String,
+// One block of the Stats panel, a heading over a few name and value lines
+pub struct StatsEvent {
+    pub title: String,
+    pub lines: Vec<(String, String)>,
 }
-impl ToRM for SalmonRunEvent {
-    fn to_rm(&self) -> Vec<RmObject> {
-        let mut ret = Vec::new();
-        ret.append(&mut {
-            let mut ret = self.run_time.to_rm();
-            for obj in ret.iter_mut() {
-                if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                    o.pos += (0,0).into();
+impl ToRM for StatsEvent {
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
+        let title = RmObject::new(ObjectType::meter(
+            MeterType::String(
+                {
+                    let mut ret = style.theme.string_options();
+                    ret.text = self.title.clone();
+                    ret
                 }
+            ),
+            {
+                let mut ret = MeterOptions::new();
+                ret.pos = (150,15).into();
+                ret.size = (300,30).into();
+                ret.solid_color = Some(style.theme.panel.clone());
+                ret
             }
-            ret
-        });
-        ret.append(&mut {
-            let mut ret = self.coop_setting.to_rm();
-            for obj in ret.iter_mut() {
-                if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                    o.pos += (100, 0).into();
+        )).prefix_name_owned("Title");
+        let lines = self.lines.iter().enumerate().map(|(i, (name, value))| {
+            let cell = |text: &str, align: StringAlign, x: isize, width: isize| RmObject::new(ObjectType::meter(
+                MeterType::String(
+                    style.theme.small_string_options().with_text(text).with_align(align)
+                ),
+                {
+                    let mut ret = MeterOptions::new();
+                    ret.pos = (x,10).into();
+                    ret.size = (width,20).into();
+                    ret.solid_color = Some(style.theme.cell.clone());
+                    ret
                 }
+            ));
+            let mut ret = Container::row()
+                .push(cell(name, StringAlign::LeftCenter, 5, 170).prefix_name_owned("Name"))
+                .push(cell(value, StringAlign::RightCenter, 120, 120).prefix_name_owned("Value"))
+                .build();
+            for obj in ret.objects.iter_mut() {
+                obj.prefix_name_mut(&format!("Line{i}"));
             }
             ret
         });
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::Image(
-                        ImageOptions{
-                            image_name: format!("#@#King Salmonids/{}.png", self.king_guess),
-                            preseve_aspect_ratio: false,
-                        }
-                    ),
+        Container::column()
+            .padding((0,5))
+            .push(title)
+            .push_all(lines)
+            .build()
+            .into_objects()
+    }
+}
+impl Query for StatsEvent {
+    fn run_time(&self) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        None
+    }
+    fn query(&self, field: &str) -> Option<String> {
+        self.lines.iter().find(|(name, _)| name.eq_ignore_ascii_case(field)).map(|(_, value)| value.clone())
+    }
+    fn summary(&self) -> String {
+        format!("{}: {}", self.title, self.lines.iter().map(|(name, value)| format!("{name} {value}")).collect::<Vec<_>>().join(", "))
+    }
+}
+impl Download for StatsEvent {
+    fn download(&self, _dir_path: &str) -> Vec<Result<(), String>> {
+        Vec::new()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SalmonRunKind {
+    Regular,
+    BigRun,
+    EggstraWork,
+}
+impl SalmonRunKind {
+    pub fn parse(name: &str) -> Option<SalmonRunKind> {
+        match name.to_lowercase().replace([' ', '-', '_'], "").as_str() {
+            "regular" => Some(SalmonRunKind::Regular),
+            "bigrun" => Some(SalmonRunKind::BigRun),
+            "eggstrawork" | "eggstra" => Some(SalmonRunKind::EggstraWork),
+            _ => None,
+        }
+    }
+    pub fn label(&self) -> &'static str {
+        match self {
+            SalmonRunKind::Regular => "Salmon Run",
+            SalmonRunKind::BigRun => "Big Run",
+            SalmonRunKind::EggstraWork => "Eggstra Work",
+        }
+    }
+    // Cell color of the weapons, regular shifts keep the normal cell color
+    fn color(&self, style: &Style) -> Option<Color> {
+        match self {
+            SalmonRunKind::Regular => None,
+            SalmonRunKind::BigRun => Some(style.theme.special.clone()),
+            SalmonRunKind::EggstraWork => Some(style.theme.eggstra_work.clone()),
+        }
+    }
+}
+
+pub struct SalmonRunEvent {
+    pub run_time: (DateTime<Local>, DateTime<Local>),
+    pub coop_setting: SalmonRunSetting,
+    pub kind: SalmonRunKind,
+    // Eggstra Work has no King Salmonid
+    pub king_guess: Option<String>,
+}
+impl ToRM for SalmonRunEvent {
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
+        let mut setting = Container::stack().align(Align::Start)
+            .push(self.coop_setting.get_rm_objects(style, self.kind.color(style)));
+        if self.kind != SalmonRunKind::Regular {
+            setting = setting.push(RmObject::new(ObjectType::meter(
+                MeterType::String(
                     {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (400, 0).into();
-                        ret.size = (50, 50).into();
-                        ret.solid_color = Some((75,50,50,255).into());
-                        ret.tool_tip = Some(ToolTip::new(self.king_guess.clone()));
+                        let mut ret = style.theme.small_string_options();
+                        ret.text = self.kind.label().to_string();
                         ret
                     }
-                )
-            ).prefix_name_owned("King")
-        );
-        ret
+                ),
+                {
+                    let mut ret = MeterOptions::new();
+                    ret.pos = (50,7).into();
+                    ret.size = (100,14).into();
+                    ret.solid_color = self.kind.color(style);
+                    ret
+                }
+            )).prefix_name_owned("Kind"));
+        }
+        Container::row()
+            .push(self.run_time.to_rm(style))
+            .push(setting.build())
+            .push(
+                RmObject::new(
+                    ObjectType::meter(
+                        MeterType::Image(
+                            ImageOptions{
+                                image_name: format!("#@#King Salmonids/{}.png", self.king_guess.as_deref().unwrap_or("None")),
+                                ..ImageOptions::default()
+                            }
+                        ),
+                        {
+                            let mut ret = MeterOptions::new();
+                            ret.size = (50, 50).into();
+                            ret.solid_color = Some(style.theme.king.clone());
+                            ret.tool_tip = Some(ToolTip::new(self.king_guess.clone().unwrap_or("No King Salmonid".to_string())));
+                            ret
+                        }
+                    )
+                ).prefix_name_owned("King")
+            )
+            .build()
+            .into_objects()
+    }
+}
+impl Query for SalmonRunEvent {
+    fn run_time(&self) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        Some(self.run_time)
+    }
+    fn query(&self, field: &str) -> Option<String> {
+        let field = field.to_lowercase();
+        match field.as_str() {
+            "stage" => Some(self.coop_setting.coop_stage.name.clone()),
+            "king" => Some(self.king_guess.clone().unwrap_or("None".to_string())),
+            "kind" => Some(self.kind.label().to_string()),
+            "bigrun" => Some(if self.kind == SalmonRunKind::BigRun {"1"} else {"0"}.to_string()),
+            "random" => Some(self.coop_setting.random_kind().map(|k| k.label()).unwrap_or("None").to_string()),
+            _ => field.strip_prefix("weapon")
+                .and_then(|i| i.parse::<usize>().ok())
+                .and_then(|i| self.coop_setting.weapons.get(i.checked_sub(1)?))
+                .map(|w| w.name.clone()),
+        }
+    }
+    fn summary(&self) -> String {
+        format!("{}: {} with {}", self.kind.label(), self.coop_setting.coop_stage.name, self.coop_setting.weapons.iter().map(|w| w.name.as_str()).collect::<Vec<_>>().join(", "))
     }
 }
 impl Download for SalmonRunEvent {
@@ -1200,35 +1699,60 @@ impl Download for SalmonRunEvent {
 pub struct SalmonRunSetting {
     pub coop_stage: Stage,
     pub weapons: Vec<Weapon>,
-    pub special: bool,
 }
-impl ToRM for SalmonRunSetting {
-    fn to_rm(&self) -> Vec<RmObject> {
-        let mut ret = Vec::new();
-        ret.push({
-            let mut ret = self.coop_stage.get_rm_object();
-            ret.prefix_name_mut("Stage");
-            if let ObjectType::Meter(_, ref mut o) = ret.object_type {
-                o.pos += (0,0).into();
-            }
-            ret
-        });
-        ret.append(&mut {
-            let mut ret = Vec::new();
-            for (i, weapon) in self.weapons.iter().enumerate() {
-                let mut obj = weapon.get_rm_object();
+impl SalmonRunSetting {
+    // `weapon_color` replaces the weapons' cell color for Big Runs and Eggstra Work
+    fn get_rm_objects(&self, style: &Style, weapon_color: Option<Color>) -> Vec<RmObject> {
+        let weapons = Container::row()
+            .push_all(self.weapons.iter().enumerate().map(|(i, weapon)| {
+                let mut obj = weapon.get_rm_object(style);
                 obj.prefix_name_mut(&format!("Weapon{i}"));
                 if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                    o.pos += (100 + 50 * i as isize, 0).into();
-                    if self.special {
-                        o.solid_color = Some((150,150,30,255).into());
+                    if let Some(color) = &weapon_color {
+                        o.solid_color = Some(color.clone());
                     }
                 }
-                ret.push(obj);
-            }
-            ret
-        });
-        ret
+                obj
+            }))
+            .build();
+        let mut weapons = Container::stack().align(Align::Start).push(weapons);
+        // Random weapons look like any other "?" icon, so the rotation gets a label
+        if let Some(kind) = self.random_kind() {
+            weapons = weapons.push(RmObject::new(ObjectType::meter(
+                MeterType::String(
+                    {
+                        let mut ret = style.theme.small_string_options();
+                        ret.text = kind.label().to_string();
+                        ret
+                    }
+                ),
+                {
+                    let mut ret = MeterOptions::new();
+                    ret.pos = (25,7).into();
+                    ret.size = (50,14).into();
+                    ret.solid_color = Some(if kind == WeaponKind::RareRandom {style.theme.special.clone()} else {style.theme.panel.clone()});
+                    ret
+                }
+            )).prefix_name_owned("RandomBadge"));
+        }
+        let weapons = weapons.build();
+        let weapons = if style.weapons.score(&self.weapons) > 0 {
+            let outline = new_outline(weapons.size.clone(), style.theme.favorite.clone()).prefix_name_owned("Preferred");
+            Container::stack().push(weapons).push(outline).build()
+        } else {
+            weapons
+        };
+        Container::row()
+            .push(self.coop_stage.get_rm_objects(style, "Stage"))
+            .push(weapons)
+            .build()
+            .into_objects()
+    }
+}
+impl SalmonRunSetting {
+    // The rarest random weapon of the rotation, if it has any
+    pub fn random_kind(&self) -> Option<WeaponKind> {
+        self.weapons.iter().map(|w| w.kind).max().filter(|k| *k != WeaponKind::Normal)
     }
 }
 impl Download for SalmonRunSetting {
@@ -1240,32 +1764,69 @@ impl Download for SalmonRunSetting {
     }
 }
 
+// splatoon3.ink ids of the "?" weapons. The green one can be any weapon and the
+// golden one only rare weapons
+const RANDOM_WEAPON_ID: &str = "747937841598fff7";
+const RARE_RANDOM_WEAPON_ID: &str = "6e17fbe20efecca9";
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WeaponKind {
+    Normal,
+    Random,
+    RareRandom,
+}
+impl WeaponKind {
+    pub fn classify(id: &str) -> WeaponKind {
+        match id {
+            RARE_RANDOM_WEAPON_ID => WeaponKind::RareRandom,
+            RANDOM_WEAPON_ID => WeaponKind::Random,
+            _ => WeaponKind::Normal,
+        }
+    }
+    pub fn label(&self) -> &'static str {
+        match self {
+            WeaponKind::Normal => "",
+            WeaponKind::Random => "Random",
+            WeaponKind::RareRandom => "Rare",
+        }
+    }
+}
+
 pub struct Weapon {
     pub name: String,
     pub image: Image,
+    pub kind: WeaponKind,
 }
 impl Weapon {
-    pub fn get_rm_object(&self) -> RmObject {
+    pub fn get_rm_object(&self, style: &Style) -> RmObject {
         RmObject::new(
-            ObjectType::Meter(
+            ObjectType::meter(
                 MeterType::Image(ImageOptions {
-                    image_name: format!("#@#Weapons/{}.png", self.name),
-                    preseve_aspect_ratio: false,
+                    image_name: format!("#@#Weapons/{}.png", self.file_name()),
+                    greyscale: style.weapons.is_avoided(&self.name),
+                    ..ImageOptions::default()
                 }),
                 {
                     let mut ret = MeterOptions::new();
                     ret.size = (50,50).into();
-                    ret.solid_color = Some((30,30,30,255).into());
+                    ret.solid_color = Some(if style.weapons.is_preferred(&self.name) {style.theme.favorite.clone()} else {style.theme.cell.clone()});
                     ret.tool_tip = Some(ToolTip::new(self.name.clone()));
                     ret
                 }
             )
         )
     }
+    // Both random icons are named "Random", so the golden one needs its own file
+    fn file_name(&self) -> String {
+        match self.kind {
+            WeaponKind::RareRandom => format!("{} Rare", self.name),
+            _ => self.name.clone(),
+        }
+    }
 }
 impl Download for Weapon {
     fn download(&self, dir_path: &str) -> Vec<Result<(), String>> {
-        vec![self.image.download(&self.name, &format!("{dir_path}/Weapons"))]
+        vec![self.image.download(&self.file_name(), &format!("{dir_path}/Weapons"))]
     }
 }
 
@@ -1273,298 +1834,427 @@ pub struct Splatfest {
     pub run_time: (DateTime<Local>, DateTime<Local>),
     pub title: String,
     pub teams: (SplatfestTeam, SplatfestTeam, SplatfestTeam),
+    pub undecided_votes: Option<usize>,
     pub state: SplatfestState,
 }
 impl ToRM for Splatfest {
-    fn to_rm(&self) -> Vec<RmObject> {
-        let mut ret = Vec::new();
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::String(
-                        {
-                            let mut ret = StringOptions::default();
-                            ret.text = self.title.clone();
-                            ret
-                        }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (250, 25).into();
-                        ret.size = (300, 50).into();
-                        ret.solid_color = Some((50, 50, 50, 255).into());
-                        ret
-                    }
-                )
-            ).prefix_name_owned("Title")
-        );
-        for (i, team) in <[SplatfestTeam; 3]>::from(self.teams.clone()).iter().enumerate() {
-            ret.push(
-                RmObject::new(
-                    ObjectType::Meter(
-                        MeterType::Image(
-                            ImageOptions{
-                                image_name: format!("#@#Splatfest Teams/{}", team.name),
-                                preseve_aspect_ratio: true,
-                            }
-                        ),
-                        {
-                            let mut ret = MeterOptions::new();
-                            ret.pos = (100 + i as isize * 100, 50).into();
-                            ret.size = (100, 50).into();
-                            ret.solid_color = Some(team.color.clone());
-                            ret
-                        }
-                    )
-                ).prefix_name_owned(&format!("Team{i}Image"))
-            );
-            ret.push(
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
+        let teams = <[SplatfestTeam; 3]>::from(self.teams.clone());
+        let info = Container::column()
+            .push(
                 RmObject::new(
-                    ObjectType::Meter(
+                    ObjectType::meter(
                         MeterType::String(
                             {
-                                let mut ret = StringOptions::default();
-                                ret.text = team.name.clone();
+                                let mut ret = style.theme.string_options();
+                                ret.text = self.title.clone();
                                 ret
                             }
                         ),
                         {
                             let mut ret = MeterOptions::new();
-                            ret.pos = (150 + i as isize * 100, 125).into();
-                            ret.size = (100, 50).into();
-                            ret.solid_color = Some(team.color.clone());
+                            ret.pos = (150, 25).into();
+                            ret.size = (300, 50).into();
+                            ret.solid_color = Some(style.theme.background.clone());
                             ret
                         }
                     )
-                ).prefix_name_owned(&format!("Team{i}Name"))
-            );
-        }
-        match &self.state {
-            SplatfestState::MissingTricolor => {
-                ret.push(
-                    RmObject::new(
-                        ObjectType::Meter(
-                            MeterType::Image(
-                                ImageOptions{
-                                    image_name: String::new(),
-                                    preseve_aspect_ratio: false,
+                ).prefix_name_owned("Title")
+            )
+            .push(
+                Container::row()
+                    .push_all(teams.iter().enumerate().map(|(i, team)|
+                        RmObject::new(
+                            ObjectType::meter(
+                                MeterType::Image(
+                                    ImageOptions{
+                                        image_name: format!("#@#Splatfest Teams/{}", team.name),
+                                        preseve_aspect_ratio: true,
+                                        ..ImageOptions::default()
+                                    }
+                                ),
+                                {
+                                    let mut ret = MeterOptions::new();
+                                    ret.size = (100, 50).into();
+                                    ret.solid_color = Some(team.color.clone());
+                                    ret
                                 }
-                            ),
-                            {
-                                let mut ret = MeterOptions::new();
-                                ret.size = (100,150).into();
-                                ret.solid_color = Some((50,50,50,255).into());
-                                ret
-                            }
-                        )
-                    ).prefix_name_owned("Background")
-                );
-                ret.append(&mut {
-                    let mut ret = new_timebar(&self.run_time.0, &self.run_time.1);
-                    for obj in ret.iter_mut() {
-                        obj.prefix_name_mut("FullTerm");
-                        if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                            o.pos += (0,50).into();
+                            )
+                        ).prefix_name_owned(&format!("Team{i}Image"))
+                    ))
+                    .build()
+            )
+            .push(
+                Container::row()
+                    .push_all(teams.iter().enumerate().map(|(i, team)|
+                        RmObject::new(
+                            ObjectType::meter(
+                                MeterType::String(
+                                    {
+                                        let mut ret = style.theme.string_options();
+                                        ret.text = team.name.clone();
+                                        ret
+                                    }
+                                ),
+                                {
+                                    let mut ret = MeterOptions::new();
+                                    ret.pos = (50, 25).into();
+                                    ret.size = (100, 50).into();
+                                    ret.solid_color = Some(team.color.clone());
+                                    ret
+                                }
+                            )
+                        ).prefix_name_owned(&format!("Team{i}Name"))
+                    ))
+                    .build()
+            )
+            .build();
+
+        let ret = match &self.state {
+            SplatfestState::MissingTricolor => {
+                let side = Container::stack().align(Align::Center)
+                    .push(new_panel((100,150).into(), style.theme.background.clone()).prefix_name_owned("Background"))
+                    .push({
+                        let mut ret = new_timebar(&self.run_time.0, &self.run_time.1, style);
+                        for obj in ret.iter_mut() {
+                            obj.prefix_name_mut("FullTerm");
                         }
-                    }
-                    ret
-                });
+                        ret
+                    })
+                    .build();
+                Container::row().push(side).push(info).build()
             },
             SplatfestState::Active(mid_term, tricolor_stage) => {
-                ret.append(&mut {
-                    let mut ret = new_timebar(&self.run_time.0, mid_term);
-                    for obj in ret.iter_mut() {
-                        obj.prefix_name_mut("FirstTerm");
-                        if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                            o.pos += (0,0).into();
+                let side = Container::column()
+                    .push({
+                        let mut ret = new_timebar(&self.run_time.0, mid_term, style);
+                        for obj in ret.iter_mut() {
+                            obj.prefix_name_mut("FirstTerm");
                         }
-                    }
-                    ret
-                });
-                ret.push({
-                    let mut ret = tricolor_stage.get_rm_object();
-                    ret.prefix_name_mut("TricolorStage");
-                    if let ObjectType::Meter(_, ref mut o) = ret.object_type {
-                        o.pos += (0,50).into();
-                    }
-                    ret
-                });
-                ret.append(&mut {
-                    let mut ret = new_timebar(mid_term, &self.run_time.1);
-                    for obj in ret.iter_mut() {
-                        obj.prefix_name_mut("SecondTerm");
-                        if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                            o.pos += (0,100).into();
+                        ret
+                    })
+                    .push(tricolor_stage.get_rm_object(style).prefix_name_owned("TricolorStage"))
+                    .push({
+                        let mut ret = new_timebar(mid_term, &self.run_time.1, style);
+                        for obj in ret.iter_mut() {
+                            obj.prefix_name_mut("SecondTerm");
                         }
-                    }
-                    ret
-                });
+                        ret
+                    })
+                    .build();
+                Container::row().push(side).push(info).build()
             },
             SplatfestState::Finished(results) => {
-                ret.push(
-                    RmObject::new(
-                        ObjectType::Meter(
-                            MeterType::String(
-                                {
-                                    let mut ret = StringOptions::default();
-                                    ret.text = results.winner.name.clone();
-                                    ret
-                                }
-                            ),
-                            {
-                                let mut ret = MeterOptions::new();
-                                ret.pos = (50,25).into();
-                                ret.size = (100,50).into();
-                                ret.solid_color = Some(results.winner.color.clone());
-                                ret
-                            }
-                        )
-                    ).prefix_name_owned("WinnerTeamName")
-                );
-                ret.push(
-                    RmObject::new(
-                        ObjectType::Meter(
-                            MeterType::Image(
-                                ImageOptions{
-                                    image_name: format!("#@#Splatfest Teams/{}", results.winner.name),
-                                    preseve_aspect_ratio: false,
-                                }
-                            ),
-                            {
-                                let mut ret = MeterOptions::new();
-                                ret.pos = (0,50).into();
-                                ret.size = (100,100).into();
-                                ret.solid_color = Some(results.winner.color.clone());
-                                ret
-                            }
-                        )
-                    ).prefix_name_owned("WinnerTeamImage")
-                );
-                ret.push(
-                    RmObject::new(
-                        ObjectType::Meter(
-                            MeterType::String(
-                                {
-                                    let mut ret = StringOptions::default();
-                                    ret.text = "Sneak Peak".to_string();
-                                    ret
-                                }
-                            ),
-                            {
-                                let mut ret = MeterOptions::new();
-                                ret.pos = (50, 175).into();
-                                ret.size = (100, 50).into();
-                                ret.solid_color = Some((50,50,50,255).into());
-                                ret
-                            }
-                        )
-                    ).prefix_name_owned("SneakPeakRow")
-                );
-                ret.push(
-                    RmObject::new(
-                        ObjectType::Meter(
-                            MeterType::String(
-                                {
-                                    let mut ret = StringOptions::default();
-                                    ret.text = "Votes".to_string();
-                                    ret
-                                }
-                            ),
-                            {
-                                let mut ret = MeterOptions::new();
-                                ret.pos = (50, 225).into();
-                                ret.size = (100, 50).into();
-                                ret.solid_color = Some((50,50,50,255).into());
-                                ret
-                            }
-                        )
-                    ).prefix_name_owned("VotesRow")
-                );
-                ret.push(
-                    RmObject::new(
-                        ObjectType::Meter(
-                            MeterType::String(
-                                {
-                                    let mut ret = StringOptions::default();
-                                    ret.text = "Open".to_string();
-                                    ret
-                                }
-                            ),
-                            {
-                                let mut ret = MeterOptions::new();
-                                ret.pos = (50, 275).into();
-                                ret.size = (100, 50).into();
-                                ret.solid_color = Some((50,50,50,255).into());
-                                ret
-                            }
-                        )
-                    ).prefix_name_owned("OpenRow")
-                );
-                ret.push(
-                    RmObject::new(
-                        ObjectType::Meter(
-                            MeterType::String(
+                let side = Container::column()
+                    .push(
+                        RmObject::new(
+                            ObjectType::meter(
+                                MeterType::String(
+                                    {
+                                        let mut ret = style.theme.string_options();
+                                        ret.text = results.winner.name.clone();
+                                        ret
+                                    }
+                                ),
                                 {
-                                    let mut ret = StringOptions::default();
-                                    ret.text = "Pro".to_string();
+                                    let mut ret = MeterOptions::new();
+                                    ret.pos = (50,25).into();
+                                    ret.size = (100,50).into();
+                                    ret.solid_color = Some(results.winner.color.clone());
                                     ret
                                 }
-                            ),
-                            {
-                                let mut ret = MeterOptions::new();
-                                ret.pos = (50, 325).into();
-                                ret.size = (100, 50).into();
-                                ret.solid_color = Some((50,50,50,255).into());
-                                ret
-                            }
-                        )
-                    ).prefix_name_owned("ProRow")
-                );
-                ret.push(
-                    RmObject::new(
-                        ObjectType::Meter(
-                            MeterType::String(
+                            )
+                        ).prefix_name_owned("WinnerTeamName")
+                    )
+                    .push(
+                        RmObject::new(
+                            ObjectType::meter(
+                                MeterType::Image(
+                                    ImageOptions{
+                                        image_name: format!("#@#Splatfest Teams/{}", results.winner.name),
+                                        ..ImageOptions::default()
+                                    }
+                                ),
                                 {
-                                    let mut ret = StringOptions::default();
-                                    ret.text = "Tricolor".to_string();
+                                    let mut ret = MeterOptions::new();
+                                    ret.size = (100,100).into();
+                                    ret.solid_color = Some(results.winner.color.clone());
                                     ret
                                 }
-                            ),
+                            )
+                        ).prefix_name_owned("WinnerTeamImage")
+                    )
+                    .build();
+                let label = |text: String, name: &str| RmObject::new(
+                    ObjectType::meter(
+                        MeterType::String(
                             {
-                                let mut ret = MeterOptions::new();
-                                ret.pos = (50, 375).into();
-                                ret.size = (100, 50).into();
-                                ret.solid_color = Some((50,50,50,255).into());
+                                let mut ret = style.theme.string_options();
+                                ret.text = text;
                                 ret
                             }
-                        )
-                    ).prefix_name_owned("TricolorRow")
-                );
-                for (i, result) in <[SplatfestTeamResult; 3]>::from(results.team_results.clone()).iter().enumerate() {
-                    ret.append(&mut {
-                        let mut ret = result.to_rm();
+                        ),
+                        {
+                            let mut ret = MeterOptions::new();
+                            ret.pos = (50, 25).into();
+                            ret.size = (100, 50).into();
+                            ret.solid_color = Some(style.theme.background.clone());
+                            ret
+                        }
+                    )
+                ).prefix_name_owned(name);
+                let labels = Container::column()
+                    .push_all(ResultCategory::ALL.iter().map(|c| label(format!("{}#CRLF#{}p", c.label(), c.points()), &format!("{}Row", c.name()))))
+                    .push(label("Total".to_string(), "TotalRow"))
+                    .build();
+                let team_results = <[SplatfestTeamResult; 3]>::from(results.team_results.clone());
+                let top_points = team_results.iter().map(|r| r.total_points()).max().unwrap_or(0);
+                let table = Container::row()
+                    .push(labels)
+                    .push_all(team_results.iter().enumerate().map(|(i, result)| {
+                        let mut ret = result.to_rm(style);
+                        ret.push(RmObject::new(
+                            ObjectType::meter(
+                                MeterType::String(
+                                    {
+                                        let mut ret = style.theme.string_options();
+                                        ret.text = format!("{}p", result.total_points());
+                                        if result.total_points() == top_points {
+                                            ret.font_color = Some(style.theme.highlight_text.clone());
+                                        }
+                                        ret
+                                    }
+                                ),
+                                MeterOptions::new().with_pos((50, 25 + 50 * ResultCategory::ALL.len() as isize)).with_size((100,50))
+                            )
+                        ).prefix_name_owned("Total"));
                         for obj in ret.iter_mut() {
                             obj.prefix_name_mut(&format!("Team{i}"));
                             if let ObjectType::Meter(_, ref mut o) = obj.object_type {
-                                o.pos += (100 + 100 * i as isize,150).into();
-                                o.solid_color = Some(<[SplatfestTeam; 3]>::from(self.teams.clone())[i].color.clone());
+                                o.solid_color = Some(teams[i].color.clone());
                             }
                         }
                         ret
-                    });
-                }
+                    }))
+                    .build();
+                Container::column()
+                    .push(Container::row().push(side).push(info).build())
+                    .push(table)
+                    .build()
             },
-        }
+        };
+        let ret = Container::column().align(Align::Center)
+            .push(ret)
+            .push({
+                let mut ret = self.timeline(style);
+                for obj in ret.objects.iter_mut() {
+                    obj.prefix_name_mut("Timeline");
+                }
+                ret
+            })
+            .push_all(self.vote_totals(style).map(|mut ret| {
+                for obj in ret.objects.iter_mut() {
+                    obj.prefix_name_mut("Votes");
+                }
+                ret
+            }))
+            .build();
 
+        let mut ret = ret.into_objects();
         for ele in ret.iter_mut() {
             ele.prefix_name_mut("Splatfest");
-            if let ObjectType::Meter(_, ref mut o) = ele.object_type {
-                o.pos += (25,0).into();
-            }
         }
         ret
     }
 }
+impl Splatfest {
+    // The midterm only comes with the schedule data once the fest is on, before that
+    // it's taken to be halfway through like it always has been
+    fn midterm(&self) -> DateTime<Local> {
+        match &self.state {
+            SplatfestState::Active(mid_term, _) => *mid_term,
+            _ => self.run_time.0 + (self.run_time.1 - self.run_time.0) / 2,
+        }
+    }
+    // Votes per team with a bar in the team's color, until the results replace them.
+    // Shares are of the decided votes, undecided ones are shown on their own
+    fn vote_totals(&self, style: &Style) -> Option<Block> {
+        if let SplatfestState::Finished(_) = self.state {
+            return None;
+        }
+        let teams = <[SplatfestTeam; 3]>::from(self.teams.clone());
+        if teams.iter().all(|t| t.vote_count().is_none()) {
+            return None;
+        }
+        let total: usize = teams.iter().filter_map(|t| t.vote_count()).sum();
+        let bar_width: isize = 150;
+        let row = |name: &str, color: Color, count: usize, share: f64, tool_tip: String| {
+            let label = RmObject::new(ObjectType::meter(
+                MeterType::String(
+                    {
+                        let mut ret = style.theme.small_string_options();
+                        ret.text = name.to_string();
+                        ret
+                    }
+                ),
+                MeterOptions::new().with_pos((50,12)).with_size((100,25)).with_solid_color(color.clone()).with_tool_tip(ToolTip::new(tool_tip))
+            )).prefix_name_owned("Name");
+            let filled = (share * bar_width as f64).round() as isize;
+            let bar = Container::stack()
+                .push(new_panel((bar_width,25).into(), style.theme.background.clone()).prefix_name_owned("Background"))
+                .push_all((filled > 0).then(|| new_panel((filled,25).into(), color).prefix_name_owned("Bar")))
+                .push(RmObject::new(ObjectType::meter(
+                    MeterType::String(
+                        {
+                            let mut ret = style.theme.small_string_options();
+                            ret.text = format!("{count} ({:.1}%)", share * 100.0);
+                            ret
+                        }
+                    ),
+                    MeterOptions::new().with_pos((bar_width / 2, 12)).with_size((bar_width, 25))
+                )).prefix_name_owned("Count"))
+                .build();
+            Container::row().push(label).push(bar).build()
+        };
+        let mut rows = Vec::new();
+        for team in teams.iter() {
+            let count = team.vote_count().unwrap_or(0);
+            let tool_tip = [("Pre-votes", team.pre_votes), ("Votes", team.votes)].iter()
+                .filter_map(|(k, v)| Some(format!("{k}: {}", (*v)?)))
+                .collect::<Vec<_>>()
+                .join("#CRLF#");
+            rows.push(row(&team.name, team.color.clone(), count, count as f64 / total.max(1) as f64, tool_tip));
+        }
+        if let Some(undecided) = self.undecided_votes {
+            let all = total + undecided;
+            rows.push(row("Undecided", style.theme.cell.clone(), undecided, undecided as f64 / all.max(1) as f64, format!("Of all {all} votes")));
+        }
+        Some(
+            Container::column()
+                .push_all(rows.into_iter().enumerate().map(|(i, mut row)| {
+                    for obj in row.objects.iter_mut() {
+                        obj.prefix_name_mut(&format!("Row{i}"));
+                    }
+                    row
+                }))
+                .push(RmObject::new(ObjectType::meter(
+                    MeterType::String(
+                        {
+                            let mut ret = style.theme.small_string_options();
+                            ret.text = format!("{total} votes");
+                            ret
+                        }
+                    ),
+                    MeterOptions::new().with_pos((125,12)).with_size((250,25)).with_solid_color(style.theme.panel.clone())
+                )).prefix_name_owned("Total"))
+                .build()
+        )
+    }
+    // Voting, both halves and the results. The halves have a countdown and how far along they are
+    fn timeline(&self, style: &Style) -> Block {
+        let now = chrono::Local::now();
+        let (start, end) = self.run_time;
+        let mid_term = self.midterm();
+        let label = |text: &str, current: bool| RmObject::new(ObjectType::meter(
+            MeterType::String(
+                {
+                    let mut ret = style.theme.small_string_options();
+                    ret.text = text.to_string();
+                    if current {
+                        ret.font_color = Some(style.theme.highlight_text.clone());
+                    }
+                    ret
+                }
+            ),
+            {
+                let mut ret = MeterOptions::new();
+                ret.pos = (50,12).into();
+                ret.size = (100,25).into();
+                ret.solid_color = Some(style.theme.panel.clone());
+                ret
+            }
+        )).prefix_name_owned("Label");
+        let text_row = |text: &str, current: bool, value: String| Container::row()
+            .push(label(text, current))
+            .push(RmObject::new(ObjectType::meter(
+                MeterType::String(
+                    {
+                        let mut ret = style.theme.small_string_options();
+                        ret.text = value;
+                        ret
+                    }
+                ),
+                MeterOptions::new().with_pos((75,12)).with_size((150,25)).with_solid_color(style.theme.background.clone())
+            )).prefix_name_owned("Text"))
+            .build();
+        // The feed doesn't say when voting opened, so there's nothing to measure it against
+        let mut rows = vec![if start <= now {
+            (text_row("Voting", false, "Closed".to_string()), true)
+        } else {
+            (text_row("Voting", true, format!("Until {}", start.format(DISPLAY_TIME_FORMAT))), false)
+        }];
+        let phases = [("First Half", start, mid_term), ("Second Half", mid_term, end)];
+        rows.extend(phases.iter().map(|(text, phase_start, phase_end)| {
+            let over = *phase_end <= now;
+            let current = !over && *phase_start <= now;
+            let mut progress = vec![
+                RmObject::new(ObjectType::Measure(
+                    MeasureType::Plugin(
+                        PluginType::Splatink(
+                            SplatinkType::TimeBar(TimeBarOptions {
+                                start_time: phase_start.timestamp(),
+                                end_time: phase_end.timestamp(),
+                                variable: None,
+                            })
+                        )
+                    ),
+                    MeasureOptions::default()
+                )).prefix_name_owned("Measure"),
+                RmObject::new(ObjectType::meter(
+                    MeterType::Bar(
+                        BarOptions::new(style.theme.time_bar.clone(), BarOrientation::Horizontal)
+                    ),
+                    MeterOptions::new().with_size((150,25)).with_solid_color(style.theme.background.clone())
+                )).prefix_name_owned("Bar"),
+                RmObject::new(ObjectType::meter(
+                    MeterType::String(
+                        {
+                            let mut ret = style.theme.small_string_options();
+                            ret.text = "%1".to_string();
+                            ret
+                        }
+                    ),
+                    MeterOptions::new().with_pos((75,12)).with_size((150,25))
+                )).prefix_name_owned("Countdown"),
+            ];
+            // Both meters read the same measure, so it's pointed at after they're named
+            for obj in progress.iter_mut() {
+                if let ObjectType::Meter(_, ref mut o) = obj.object_type {
+                    o.measure_name = Some("Measure".to_string());
+                }
+            }
+            (Container::row().push(label(text, current)).push(progress).build(), over)
+        }));
+        rows.push((match &self.state {
+            SplatfestState::Finished(results) => text_row("Results", false, format!("{} won", results.winner.name)),
+            _ if end <= now => text_row("Results", true, "Pending".to_string()),
+            _ => text_row("Results", false, format!("After {}", end.format(DISPLAY_TIME_FORMAT))),
+        }, false));
+        Container::column()
+            .push_all(rows.into_iter().enumerate().map(|(i, (row, over))| {
+                let size = row.size.clone();
+                let mut ret = Container::stack()
+                    .push(row)
+                    .push_all(over.then(|| new_shade(size).prefix_name_owned("Shade")))
+                    .build();
+                for obj in ret.objects.iter_mut() {
+                    obj.prefix_name_mut(&format!("Phase{i}"));
+                }
+                ret
+            }))
+            .build()
+    }
+}
 impl Download for Splatfest {
     fn download(&self, dir_path: &str) -> Vec<Result<(), String>> {
         let mut ret = Vec::new();
@@ -1583,6 +2273,14 @@ pub struct SplatfestTeam {
     pub name: String,
     pub color: Color,
     pub image: Image,
+    // Votes cast before the fest and the running total since, when splatoon3.ink has them
+    pub pre_votes: Option<usize>,
+    pub votes: Option<usize>,
+}
+impl SplatfestTeam {
+    pub fn vote_count(&self) -> Option<usize> {
+        self.votes.or(self.pre_votes)
+    }
 }
 impl Download for SplatfestTeam {
     fn download(&self, dir_path: &str) -> Vec<Result<(), String>> {
@@ -1601,133 +2299,104 @@ pub struct SplatfestResults {
     pub team_results: (SplatfestTeamResult, SplatfestTeamResult, SplatfestTeamResult),
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum ResultCategory {
+    SneakPeak,
+    Votes,
+    Open,
+    Pro,
+    Tricolor,
+}
+impl ResultCategory {
+    pub const ALL: [ResultCategory; 5] = [ResultCategory::SneakPeak, ResultCategory::Votes, ResultCategory::Open, ResultCategory::Pro, ResultCategory::Tricolor];
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResultCategory::SneakPeak => "Sneak Peak",
+            ResultCategory::Votes => "Votes",
+            ResultCategory::Open => "Open",
+            ResultCategory::Pro => "Pro",
+            ResultCategory::Tricolor => "Tricolor",
+        }
+    }
+    fn name(&self) -> &'static str {
+        match self {
+            ResultCategory::SneakPeak => "SneakPeak",
+            ResultCategory::Votes => "Votes",
+            ResultCategory::Open => "Open",
+            ResultCategory::Pro => "Pro",
+            ResultCategory::Tricolor => "Tricolor",
+        }
+    }
+    // What the team on top of the category scores, the team with the most points wins
+    pub fn points(&self) -> usize {
+        match self {
+            ResultCategory::SneakPeak => 10,
+            ResultCategory::Votes => 8,
+            ResultCategory::Open => 12,
+            ResultCategory::Pro => 12,
+            ResultCategory::Tricolor => 15,
+        }
+    }
+}
+
+// Each category is whether the team came out on top and its share
 #[derive(Clone)]
 pub struct SplatfestTeamResult {
     pub sneak_peak: (bool, f64),
     pub votes: (bool, f64),
     pub open: (bool, f64),
     pub pro: (bool, f64),
-    pub tricolor: (bool, f64),
+    // Older fests were scored without Tricolor Battles
+    pub tricolor: Option<(bool, f64)>,
+}
+impl SplatfestTeamResult {
+    pub fn category(&self, category: ResultCategory) -> Option<(bool, f64)> {
+        match category {
+            ResultCategory::SneakPeak => Some(self.sneak_peak),
+            ResultCategory::Votes => Some(self.votes),
+            ResultCategory::Open => Some(self.open),
+            ResultCategory::Pro => Some(self.pro),
+            ResultCategory::Tricolor => self.tricolor,
+        }
+    }
+    pub fn points(&self, category: ResultCategory) -> usize {
+        match self.category(category) {
+            Some((true, _)) => category.points(),
+            _ => 0,
+        }
+    }
+    pub fn total_points(&self) -> usize {
+        ResultCategory::ALL.iter().map(|c| self.points(*c)).sum()
+    }
 }
 impl ToRM for SplatfestTeamResult {
-    fn to_rm(&self) -> Vec<RmObject> {
-        let mut ret = Vec::new();
-        ret.push(
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
+        ResultCategory::ALL.iter().enumerate().map(|(i, category)| {
             RmObject::new(
-                ObjectType::Meter(
+                ObjectType::meter(
                     MeterType::String(
                         {
-                            let mut ret = StringOptions::default();
-                            ret.text = format!("{}%", self.sneak_peak.1.mul(10000.0).round().div(100.0));
-                            if self.sneak_peak.0 {
-                                ret.font_color = Some((150,150,50,255).into());
-                            }
-                            ret
-                        }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (50,25).into();
-                        ret.size = (100,50).into();
-                        ret.solid_color = Some((30,30,30,255).into());
-                        ret
-                    }
-                )
-            ).prefix_name_owned("SneakPeak")
-        );
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::String(
-                        {
-                            let mut ret = StringOptions::default();
-                            ret.text = format!("{}%", self.votes.1.mul(10000.0).round().div(100.0));
-                            if self.votes.0 {
-                                ret.font_color = Some((150,150,50,255).into());
-                            }
-                            ret
-                        }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (50,75).into();
-                        ret.size = (100,50).into();
-                        ret.solid_color = Some((30,30,30,255).into());
-                        ret
-                    }
-                )
-            ).prefix_name_owned("Votes")
-        );
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::String(
-                        {
-                            let mut ret = StringOptions::default();
-                            ret.text = format!("{}%", self.open.1.mul(10000.0).round().div(100.0));
-                            if self.open.0 {
-                                ret.font_color = Some((150,150,50,255).into());
-                            }
-                            ret
-                        }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (50,125).into();
-                        ret.size = (100,50).into();
-                        ret.solid_color = Some((30,30,30,255).into());
-                        ret
-                    }
-                )
-            ).prefix_name_owned("Open")
-        );
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::String(
-                        {
-                            let mut ret = StringOptions::default();
-                            ret.text = format!("{}%", self.pro.1.mul(10000.0).round().div(100.0));
-                            if self.pro.0 {
-                                ret.font_color = Some((150,150,50,255).into());
-                            }
-                            ret
-                        }
-                    ),
-                    {
-                        let mut ret = MeterOptions::new();
-                        ret.pos = (50,175).into();
-                        ret.size = (100,50).into();
-                        ret.solid_color = Some((30,30,30,255).into());
-                        ret
-                    }
-                )
-            ).prefix_name_owned("Pro")
-        );
-        ret.push(
-            RmObject::new(
-                ObjectType::Meter(
-                    MeterType::String(
-                        {
-                            let mut ret = StringOptions::default();
-                            ret.text = format!("{}%", self.tricolor.1.mul(10000.0).round().div(100.0));
-                            if self.tricolor.0 {
-                                ret.font_color = Some((150,150,50,255).into());
+                            let mut ret = style.theme.string_options();
+                            ret.text = match self.category(*category) {
+                                Some((_, ratio)) => format!("{}%#CRLF#{}p", ratio.mul(10000.0).round().div(100.0), self.points(*category)),
+                                None => "-".to_string(),
+                            };
+                            if self.category(*category).is_some_and(|(top, _)| top) {
+                                ret.font_color = Some(style.theme.highlight_text.clone());
                             }
                             ret
                         }
                     ),
                     {
                         let mut ret = MeterOptions::new();
-                        ret.pos = (50,225).into();
+                        ret.pos = (50, 25 + 50 * i as isize).into();
                         ret.size = (100,50).into();
-                        ret.solid_color = Some((30,30,30,255).into());
+                        ret.solid_color = Some(style.theme.cell.clone());
                         ret
                     }
                 )
-            ).prefix_name_owned("Tricolor")
-        );
-        ret
+            ).prefix_name_owned(category.name())
+        }).collect()
     }
 }
 
@@ -1738,23 +2407,23 @@ pub struct UpdateNotification {
     pub release_url: String,
 }
 impl ToRM for UpdateNotification {
-    fn to_rm(&self) -> Vec<RmObject> {
+    fn to_rm(&self, style: &Style) -> Vec<RmObject> {
         let mut ret = Vec::new();
         ret.push(
             RmObject::new(
-                ObjectType::Meter(
+                ObjectType::meter(
                     MeterType::String(
                         {
-                            let mut ret = StringOptions::default();
+                            let mut ret = style.theme.string_options();
                             ret.text = format!("{} is now available (current version: {})", self.new_version, self.old_version);
                             ret
                         }
                     ),
                     {
                         let mut ret = MeterOptions::new();
-                        ret.pos = (225,25).into();
+                        ret.pos = (125,25).into();
                         ret.size = (250,50).into();
-                        ret.solid_color = Some((150,150,30,255).into());
+                        ret.solid_color = Some(style.theme.special.clone());
                         ret.left_click_action.push(self.release_url.clone());
                         ret
                     }
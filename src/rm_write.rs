@@ -1,11 +1,52 @@
 use std::fmt::Display;
 extern crate serde;
 use self::serde::{Deserialize, Serialize};
+use crate::style::Style;
 
-#[allow(unused)]
-pub fn write_to_skin(skin_path: &str, contents: Vec<RmObject>) -> Result<(), std::io::Error>{
+pub const GENERATED_FILE_NAME: &str = "Generated.inc";
+
+pub fn skin_header(core_measure: &str, generated_file: &str) -> Vec<RmObject> {
+    let section = |name: &str, keys: &[(&str, String)]| RmObject {
+        name: name.to_string(),
+        object_type: ObjectType::Section(keys.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()),
+    };
+    vec![
+        section("Rainmeter", &[
+            ("Update", "1000".to_string()),
+            ("@Include", format!("#@#{generated_file}")),
+            ("AccurateText", "1".to_string()),
+            ("ContextTitle", "Refresh File".to_string()),
+            ("ContextAction", format!("[!CommandMeasure \"{core_measure}\" \"RefreshFile\"]")),
+            ("ContextTitle2", "Repull Data".to_string()),
+            ("ContextAction2", format!("[!CommandMeasure \"{core_measure}\" \"RepullData\"]")),
+        ]),
+        section("Metadata", &[
+            ("Name", "Splatoon 3 Rotation Display".to_string()),
+            ("Author", "gamingtime".to_string()),
+            ("Information", "Uses splatoon3.ink to display the future Splatoon 3 schedules along with upcoming and recent Splatfest data".to_string()),
+            ("Version", env!("CARGO_PKG_VERSION").to_string()),
+            ("License", "Creative Commons Attribution - Non - Commercial - Share Alike 3.0".to_string()),
+        ]),
+    ]
+}
+
+// Skins written before Generated.inc had everything in the one file, with the header
+// above and a core measure but no @Include
+pub fn is_single_file_skin(contents: &str) -> bool {
+    let sections = parse_ini(contents);
+    let has = |keys: &[(String, String)], key: &str, value: &str| keys.iter().any(|(k, v)| k.eq_ignore_ascii_case(key) && v.eq_ignore_ascii_case(value));
+    let generated_metadata = sections.iter().any(|(name, keys)| {
+        name.eq_ignore_ascii_case("Metadata") && has(keys, "Name", "Splatoon 3 Rotation Display") && has(keys, "Author", "gamingtime")
+    });
+    let core_measure = sections.iter().any(|(_, keys)| has(keys, "Measure", "Plugin") && has(keys, "Type", "Core")
+        && keys.iter().any(|(k, v)| k.eq_ignore_ascii_case("Plugin") && v.ends_with("Splatink")));
+    let includes = sections.iter().any(|(_, keys)| keys.iter().any(|(k, _)| k.to_lowercase().starts_with("@include")));
+    generated_metadata && core_measure && !includes
+}
+
+pub fn write_to_skin(skin_path: &str, contents: &[RmObject]) -> Result<(), std::io::Error>{
     std::fs::write(skin_path, {
-        let mut ret = format!("[Rainmeter]\nUpdate=1000\nAccurateText=1\nContextTitle=Refresh File\nContextAction=[!CommandMeasure \"SplatinkCore\" \"RefreshFile\"]\nContextTitle2=Repull Data\nContextAction2=[!CommandMeasure \"SplatinkCore\" \"RepullData\"]\n[Metadata]\nName=Splatoon 3 Rotation Display\nAuthor=gamingtime\nInformation=Uses splatoon3.ink to display the future Splatoon 3 schedules along with upcoming and recent Splatfest data\nVersion={}\nLicense=Creative Commons Attribution - Non - Commercial - Share Alike 3.0\n", env!("CARGO_PKG_VERSION"));
+        let mut ret = String::new();
         for obj in contents {
             ret += &format!("{obj}\n");
         }
@@ -14,7 +55,7 @@ pub fn write_to_skin(skin_path: &str, contents: Vec<RmObject>) -> Result<(), std
 }
 
 pub trait ToRM {
-    fn to_rm(&self) -> Vec<RmObject>;
+    fn to_rm(&self, style: &Style) -> Vec<RmObject>;
 }
 
 #[derive(Clone)]
@@ -48,6 +89,8 @@ pub struct Color {
     g: isize,
     b: isize,
     a: isize,
+    #[serde(skip)]
+    pub variable: Option<String>,
 }
 impl From<(isize, isize, isize, isize)> for Color {
     fn from(value: (isize, isize, isize, isize)) -> Self {
@@ -56,15 +99,37 @@ impl From<(isize, isize, isize, isize)> for Color {
             g: value.1,
             b: value.2,
             a: value.3,
+            variable: None,
+        }
+    }
+}
+impl std::str::FromStr for Color {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > 2 && s.starts_with('#') && s.ends_with('#') {
+            let mut ret: Color = (0,0,0,255).into();
+            ret.variable = Some(s[1..s.len() - 1].to_string());
+            return Ok(ret);
+        }
+        let parts = s.split(',').map(|p| p.trim().parse::<isize>()).collect::<Result<Vec<_>, _>>().map_err(|e| format!("{s}: {e:?}"))?;
+        match parts[..] {
+            [r, g, b] => Ok((r, g, b, 255).into()),
+            [r, g, b, a] => Ok((r, g, b, a).into()),
+            _ => Err(format!("{s}: Expected 3 or 4 Components")),
         }
     }
 }
+impl Color {
+    pub fn rgba(&self) -> String {
+        format!("{},{},{},{}", self.r, self.g, self.b, self.a)
+    }
+}
 impl Display for Color {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f,
-            "{},{},{},{}",
-            self.r,self.g,self.b,self.a
-        )
+        match &self.variable {
+            Some(v) => write!(f, "#{v}#"),
+            None => write!(f, "{}", self.rgba()),
+        }
     }
 }
 
@@ -74,7 +139,11 @@ pub struct RmObject {
 }
 impl Display for RmObject {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}]\n{}", self.name, self.object_type)
+        write!(f, "[{}]", self.name)?;
+        for (key, value) in self.keys() {
+            write!(f, "\n{key}={value}")?;
+        }
+        Ok(())
     }
 }
 impl RmObject {
@@ -106,59 +175,132 @@ impl RmObject {
 
 pub enum ObjectType {
     Measure(MeasureType, MeasureOptions),
-    Meter(MeterType, MeterOptions),
+    Meter(MeterType, Box<MeterOptions>),
+    Variables(Vec<(String, String)>),
+    Section(Vec<(String, String)>),
 }
-impl Display for ObjectType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ObjectType::Measure(t, o) => write!(f, "Measure={t}\n{o}"),
-            ObjectType::Meter(t, o) => write!(f, "Meter={t}\n{o}"),
-        }
+impl ObjectType {
+    pub fn meter(meter_type: MeterType, options: MeterOptions) -> ObjectType {
+        ObjectType::Meter(meter_type, Box::new(options))
     }
 }
 
 #[derive(Default)]
 pub struct MeasureOptions {
     pub on_change_action: Vec<String>,
+    pub on_update_action: Vec<String>,
     pub dynamic_variables: bool,
+    pub update_divider: Option<isize>,
+    pub disabled: bool,
+    pub paused: bool,
+    pub groups: Vec<String>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub if_conditions: Vec<IfCondition>,
+    pub substitute: Vec<(String, String)>,
+    pub regexp_substitute: bool,
+    pub other: Vec<(String, String)>,
 }
-impl Display for MeasureOptions {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut ret = String::new();
-        if !self.on_change_action.is_empty() {
-            ret += "OnChangeAction=";
-            for action in &self.on_change_action {
-                ret += &format!("[{action}]");
-            }
-            ret += "\n";
+impl MeasureOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        keys.set_actions("OnChangeAction", &self.on_change_action);
+        keys.set_actions("OnUpdateAction", &self.on_update_action);
+        keys.set_flag("DynamicVariables", self.dynamic_variables);
+        keys.set_opt("UpdateDivider", &self.update_divider);
+        keys.set_flag("Disabled", self.disabled);
+        keys.set_flag("Paused", self.paused);
+        if !self.groups.is_empty() {
+            keys.set("Group", self.groups.join("|"));
+        }
+        keys.set_opt("MinValue", &self.min_value);
+        keys.set_opt("MaxValue", &self.max_value);
+        for (i, condition) in self.if_conditions.iter().enumerate() {
+            condition.write_keys(keys, &index_suffix(i));
         }
-        if self.dynamic_variables {
-            ret += "DynamicVariables=1\n";
+        if !self.substitute.is_empty() {
+            keys.set("Substitute", self.substitute.iter().map(|(from, to)| format!("\"{from}\":\"{to}\"")).collect::<Vec<_>>().join(","));
         }
-        write!(f, "{ret}")
+        keys.set_flag("RegExpSubstitute", self.regexp_substitute);
+        keys.0.extend(self.other.iter().cloned());
+    }
+}
+
+pub struct IfCondition {
+    pub condition: String,
+    pub true_action: Vec<String>,
+    pub false_action: Vec<String>,
+}
+impl IfCondition {
+    fn write_keys(&self, keys: &mut Keys, suffix: &str) {
+        keys.set(&format!("IfCondition{suffix}"), &self.condition);
+        keys.set_actions(&format!("IfTrueAction{suffix}"), &self.true_action);
+        keys.set_actions(&format!("IfFalseAction{suffix}"), &self.false_action);
     }
 }
-#[allow(unused)]
+
+// Rainmeter numbers repeated keys as Key, Key2, Key3...
+fn index_suffix(i: usize) -> String {
+    if i == 0 {String::new()} else {format!("{}", i + 1)}
+}
+
 pub enum MeasureType {
     Plugin(PluginType),
     String(String),
+    Calc(String),
+    Time(TimeOptions),
+    Other(String),
 }
-impl Display for MeasureType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl MeasureType {
+    fn write_keys(&self, keys: &mut Keys) {
         match self {
-            MeasureType::Plugin(t) => write!(f, "Plugin\nPlugin={t}"),
-            MeasureType::String(o) => write!(f, "String\n{o}"),
+            MeasureType::Plugin(t) => {
+                keys.set("Measure", "Plugin");
+                t.write_keys(keys);
+            },
+            MeasureType::String(o) => {
+                keys.set("Measure", "String");
+                keys.set("String", o);
+            },
+            MeasureType::Calc(o) => {
+                keys.set("Measure", "Calc");
+                keys.set("Formula", o);
+            },
+            MeasureType::Time(o) => {
+                keys.set("Measure", "Time");
+                o.write_keys(keys);
+            },
+            MeasureType::Other(t) => keys.set("Measure", t),
         }
     }
 }
 
+#[derive(Default)]
+pub struct TimeOptions {
+    pub format: Option<String>,
+    pub time_stamp: Option<i64>,
+    pub time_zone: Option<f64>,
+}
+impl TimeOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        keys.set_opt("Format", &self.format);
+        if let Some(x) = &self.time_stamp {
+            keys.set("TimeStamp", x);
+            keys.set("TimeStampFormat", "%s");
+        }
+        keys.set_opt("TimeZone", &self.time_zone);
+    }
+}
+
 pub enum PluginType {
     Splatink(SplatinkType),
 }
-impl Display for PluginType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl PluginType {
+    fn write_keys(&self, keys: &mut Keys) {
         match self {
-            PluginType::Splatink(t) => write!(f, "Splatink\nType={t}"),
+            PluginType::Splatink(t) => {
+                keys.set("Plugin", "Splatink");
+                t.write_keys(keys);
+            },
         }
     }
 }
@@ -168,11 +310,17 @@ pub enum SplatinkType {
     Core(String),
     TimeBar(TimeBarOptions),
 }
-impl Display for SplatinkType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl SplatinkType {
+    fn write_keys(&self, keys: &mut Keys) {
         match self {
-            SplatinkType::Core(s) => write!(f, "Core\nSche={s}"),
-            SplatinkType::TimeBar(o) => write!(f, "TimeBar\n{o}"),
+            SplatinkType::Core(s) => {
+                keys.set("Type", "Core");
+                keys.set("Sche", s);
+            },
+            SplatinkType::TimeBar(o) => {
+                keys.set("Type", "TimeBar");
+                o.write_keys(keys);
+            },
         }
     }
 }
@@ -181,74 +329,91 @@ impl Display for SplatinkType {
 pub struct TimeBarOptions {
     pub start_time: i64,
     pub end_time: i64,
+    pub variable: Option<String>,
 }
-impl Display for TimeBarOptions {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "StartTime={}\nEndTime={}", self.start_time, self.end_time)
+impl TimeBarOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        match &self.variable {
+            Some(v) => {
+                keys.set("StartTime", format!("#{v}Start#"));
+                keys.set("EndTime", format!("#{v}End#"));
+            },
+            None => {
+                keys.set("StartTime", self.start_time);
+                keys.set("EndTime", self.end_time);
+            },
+        }
     }
 }
 
 pub struct MeterOptions {
     pub pos: Coord,
     pub size: Coord,
+    pub padding: Option<(isize, isize, isize, isize)>,
     pub solid_color: Option<Color>,
+    pub solid_color2: Option<Color>,
+    pub gradient_angle: Option<f64>,
+    pub bevel_type: Option<BevelType>,
     pub measure_name: Option<String>,
     pub groups: Vec<String>,
+    pub container: Option<String>,
     pub left_click_action: Vec<String>,
+    pub left_mouse_down_action: Vec<String>,
+    pub left_double_click_action: Vec<String>,
     pub right_click_action: Vec<String>,
+    pub middle_click_action: Vec<String>,
     pub scroll_down_action: Vec<String>,
     pub scroll_up_action: Vec<String>,
+    pub mouse_over_action: Vec<String>,
+    pub mouse_leave_action: Vec<String>,
+    pub mouse_action_cursor: Option<bool>,
     pub tool_tip: Option<ToolTip>,
+    pub update_divider: Option<isize>,
+    pub anti_alias: bool,
+    pub dynamic_variables: bool,
     pub hidden: bool,
+    pub other: Vec<(String, String)>,
 }
-impl Display for MeterOptions {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut ret = format!("X={}\nY={}\nW={}\nH={}", self.pos.x, self.pos.y, self.size.x, self.size.y);
-        if let Some(c) = &self.solid_color {
-            ret += &format!("\nSolidColor={c}");
-        }
-        if let Some(m) = &self.measure_name {
-            ret += &format!("\nMeasureName={m}");
-        }
-        if !self.groups.is_empty() {
-            ret += &format!("\nGroup=");
-            for group in &self.groups {
-                ret += group;
-                ret += "|";
-            }
-            ret.remove(ret.len() - 1);
-        }
-        if !self.left_click_action.is_empty() {
-            ret += "\nLeftMouseUpAction=";
-            for action in &self.left_click_action {
-                ret += &format!("[{action}]");
+impl MeterOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        // Positions read back from a skin can be relative or formulas, those stay in `other`
+        for (key, value) in [("X", self.pos.x), ("Y", self.pos.y), ("W", self.size.x), ("H", self.size.y)] {
+            if !self.other.iter().any(|(o, _)| o.eq_ignore_ascii_case(key)) {
+                keys.set(key, value);
             }
         }
-        if !self.right_click_action.is_empty() {
-            ret += "\nRightMouseUpAction=";
-            for action in &self.right_click_action {
-                ret += &format!("[{action}]");
-            }
+        if let Some((l, t, r, b)) = &self.padding {
+            keys.set("Padding", format!("{l},{t},{r},{b}"));
         }
-        if !self.scroll_down_action.is_empty() {
-            ret += "\nMouseScrollDownAction=";
-            for action in &self.scroll_down_action {
-                ret += &format!("[{action}]");
-            }
+        keys.set_opt("SolidColor", &self.solid_color);
+        keys.set_opt("SolidColor2", &self.solid_color2);
+        keys.set_opt("GradientAngle", &self.gradient_angle);
+        keys.set_opt("BevelType", &self.bevel_type);
+        keys.set_opt("MeasureName", &self.measure_name);
+        if !self.groups.is_empty() {
+            keys.set("Group", self.groups.join("|"));
         }
-        if !self.scroll_up_action.is_empty() {
-            ret += "\nMouseScrollUpAction=";
-            for action in &self.scroll_up_action {
-                ret += &format!("[{action}]");
-            }
+        keys.set_opt("Container", &self.container);
+        keys.set_actions("LeftMouseUpAction", &self.left_click_action);
+        keys.set_actions("LeftMouseDownAction", &self.left_mouse_down_action);
+        keys.set_actions("LeftMouseDoubleClickAction", &self.left_double_click_action);
+        keys.set_actions("RightMouseUpAction", &self.right_click_action);
+        keys.set_actions("MiddleMouseUpAction", &self.middle_click_action);
+        keys.set_actions("MouseScrollDownAction", &self.scroll_down_action);
+        keys.set_actions("MouseScrollUpAction", &self.scroll_up_action);
+        keys.set_actions("MouseOverAction", &self.mouse_over_action);
+        keys.set_actions("MouseLeaveAction", &self.mouse_leave_action);
+        if let Some(x) = &self.mouse_action_cursor {
+            keys.set("MouseActionCursor", *x as u8);
         }
         if let Some(t) = &self.tool_tip {
-            ret += &format!("\n{t}");
+            t.write_keys(keys);
         }
-        if self.hidden {
-            ret += &format!("\nHidden=1");
-        }
-        write!(f, "{ret}")
+        keys.set_opt("UpdateDivider", &self.update_divider);
+        keys.set_flag("AntiAlias", self.anti_alias);
+        keys.set_flag("DynamicVariables", self.dynamic_variables);
+        keys.set_flag("Hidden", self.hidden);
+        keys.0.extend(self.other.iter().cloned());
     }
 }
 impl MeterOptions {
@@ -256,31 +421,115 @@ impl MeterOptions {
         MeterOptions {
             pos: (0,0).into(),
             size: (0,0).into(),
+            padding: None,
             solid_color: None,
+            solid_color2: None,
+            gradient_angle: None,
+            bevel_type: None,
             measure_name: None,
             groups: Vec::new(),
+            container: None,
             left_click_action: Vec::new(),
+            left_mouse_down_action: Vec::new(),
+            left_double_click_action: Vec::new(),
             right_click_action: Vec::new(),
+            middle_click_action: Vec::new(),
             scroll_down_action: Vec::new(),
             scroll_up_action: Vec::new(),
+            mouse_over_action: Vec::new(),
+            mouse_leave_action: Vec::new(),
+            mouse_action_cursor: None,
             tool_tip: None,
+            update_divider: None,
+            anti_alias: false,
+            dynamic_variables: false,
             hidden: false,
+            other: Vec::new(),
+        }
+    }
+    pub fn with_pos(mut self, pos: impl Into<Coord>) -> Self {
+        self.pos = pos.into();
+        self
+    }
+    pub fn with_size(mut self, size: impl Into<Coord>) -> Self {
+        self.size = size.into();
+        self
+    }
+    pub fn with_solid_color(mut self, color: Color) -> Self {
+        self.solid_color = Some(color);
+        self
+    }
+    pub fn with_tool_tip(mut self, tool_tip: ToolTip) -> Self {
+        self.tool_tip = Some(tool_tip);
+        self
+    }
+}
+impl Default for MeterOptions {
+    fn default() -> Self {
+        MeterOptions::new()
+    }
+}
+
+pub enum BevelType {
+    None,
+    Raised,
+    Sunken,
+}
+impl Display for BevelType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BevelType::None => write!(f, "0"),
+            BevelType::Raised => write!(f, "1"),
+            BevelType::Sunken => write!(f, "2"),
         }
     }
 }
 
 pub struct ToolTip {
-    tool_tip_text: String,
+    pub tool_tip_text: String,
+    pub title: Option<String>,
+    pub icon: Option<ToolTipIcon>,
+    pub width: Option<usize>,
+    pub balloon: bool,
 }
-impl Display for ToolTip {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "ToolTipText={}", self.tool_tip_text)
+impl ToolTip {
+    fn write_keys(&self, keys: &mut Keys) {
+        keys.set("ToolTipText", &self.tool_tip_text);
+        keys.set_opt("ToolTipTitle", &self.title);
+        keys.set_opt("ToolTipIcon", &self.icon);
+        keys.set_opt("ToolTipWidth", &self.width);
+        keys.set_flag("ToolTipType", self.balloon);
     }
 }
 impl ToolTip {
     pub fn new(tool_tip_text: String) -> ToolTip {
         ToolTip {
-            tool_tip_text
+            tool_tip_text,
+            title: None,
+            icon: None,
+            width: None,
+            balloon: false,
+        }
+    }
+}
+
+pub enum ToolTipIcon {
+    Info,
+    Warning,
+    Error,
+    Question,
+    Shield,
+    Custom(String),
+}
+impl Display for ToolTipIcon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToolTipIcon::Info => write!(f, "Info"),
+            ToolTipIcon::Warning => write!(f, "Warning"),
+            ToolTipIcon::Error => write!(f, "Error"),
+            ToolTipIcon::Question => write!(f, "Question"),
+            ToolTipIcon::Shield => write!(f, "Shield"),
+            ToolTipIcon::Custom(path) => write!(f, "{path}"),
         }
     }
 }
@@ -289,28 +538,80 @@ pub enum MeterType {
     Image(ImageOptions),
     String(StringOptions),
     Bar(BarOptions),
+    Shape(ShapeOptions),
+    Rotator(RotatorOptions),
+    Roundline(RoundlineOptions),
+    Button(ButtonOptions),
+    Bitmap(BitmapOptions),
+    Other(String),
 }
-impl Display for MeterType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl MeterType {
+    fn write_keys(&self, keys: &mut Keys) {
+        let name = match self {
+            MeterType::Image(_) => "Image",
+            MeterType::String(_) => "String",
+            MeterType::Bar(_) => "Bar",
+            MeterType::Shape(_) => "Shape",
+            MeterType::Rotator(_) => "Rotator",
+            MeterType::Roundline(_) => "Roundline",
+            MeterType::Button(_) => "Button",
+            MeterType::Bitmap(_) => "Bitmap",
+            MeterType::Other(t) => t,
+        };
+        keys.set("Meter", name);
         match self {
-            MeterType::Image(o) => write!(f, "Image\n{o}"),
-            MeterType::String(o) => write!(f, "String\n{o}"),
-            MeterType::Bar(o) => write!(f, "Bar\n{o}"),
+            MeterType::Image(o) => o.write_keys(keys),
+            MeterType::String(o) => o.write_keys(keys),
+            MeterType::Bar(o) => o.write_keys(keys),
+            MeterType::Shape(o) => o.write_keys(keys),
+            MeterType::Rotator(o) => o.write_keys(keys),
+            MeterType::Roundline(o) => o.write_keys(keys),
+            MeterType::Button(o) => o.write_keys(keys),
+            MeterType::Bitmap(o) => o.write_keys(keys),
+            MeterType::Other(_) => {},
         }
     }
 }
 
+#[derive(Default)]
 pub struct ImageOptions {
     pub image_name: String,
     pub preseve_aspect_ratio: bool,
+    pub image_alpha: Option<u8>,
+    pub image_tint: Option<Color>,
+    pub greyscale: bool,
+    pub image_crop: Option<(isize, isize, isize, isize)>,
+    pub image_rotate: Option<f64>,
+    pub image_flip: Option<ImageFlip>,
+    pub tile: bool,
+}
+impl ImageOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        keys.set("ImageName", &self.image_name);
+        keys.set_flag("PreserveAspectRatio", self.preseve_aspect_ratio);
+        keys.set_opt("ImageAlpha", &self.image_alpha);
+        keys.set_opt("ImageTint", &self.image_tint);
+        keys.set_flag("Greyscale", self.greyscale);
+        if let Some((x, y, w, h)) = &self.image_crop {
+            keys.set("ImageCrop", format!("{x},{y},{w},{h}"));
+        }
+        keys.set_opt("ImageRotate", &self.image_rotate);
+        keys.set_opt("ImageFlip", &self.image_flip);
+        keys.set_flag("Tile", self.tile);
+    }
 }
-impl Display for ImageOptions {
+pub enum ImageFlip {
+    Horizontal,
+    Vertical,
+    Both,
+}
+impl Display for ImageFlip {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut ret = format!("ImageName={}", self.image_name);
-        if self.preseve_aspect_ratio {
-            ret.push_str(&format!("\nPreserveAspectRatio=1"))
+        match self {
+            ImageFlip::Horizontal => write!(f, "Horizontal"),
+            ImageFlip::Vertical => write!(f, "Vertical"),
+            ImageFlip::Both => write!(f, "Both"),
         }
-        write!(f, "{ret}")
     }
 }
 
@@ -318,37 +619,45 @@ pub struct StringOptions {
     pub text: String,
     pub string_align: Option<StringAlign>,
     pub font_color: Option<Color>,
+    pub font_face: Option<String>,
     pub font_size: Option<f64>,
     pub font_weight: Option<usize>,
     pub clip_string: Option<usize>,
     pub clip_string_w: Option<usize>,
+    pub clip_string_h: Option<usize>,
+    pub string_case: Option<StringCase>,
+    pub string_style: Option<StringStyle>,
+    pub string_effect: Option<StringEffect>,
+    pub font_effect_color: Option<Color>,
+    pub prefix: Option<String>,
+    pub postfix: Option<String>,
+    pub angle: Option<f64>,
+    pub inline_settings: Vec<InlineSetting>,
     pub anti_alias: bool,
 }
-impl Display for StringOptions {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut ret = format!("Text={}", self.text);
-        if let Some(x) = &self.string_align {
-            ret += &format!("\nStringAlign={x}");
-        }
-        if let Some(x) = &self.font_color {
-            ret += &format!("\nFontColor={x}");
-        }
-        if let Some(x) = &self.font_size {
-            ret += &format!("\nFontSize={x}");
-        }
-        if let Some(x) = &self.font_weight {
-            ret += &format!("\nFontWeight={x}");
-        }
-        if let Some(x) = &self.clip_string {
-            ret += &format!("\nClipString={x}");
+impl StringOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        keys.set("Text", &self.text);
+        keys.set_opt("StringAlign", &self.string_align);
+        keys.set_opt("FontColor", &self.font_color);
+        keys.set_opt("FontFace", &self.font_face);
+        keys.set_opt("FontSize", &self.font_size);
+        keys.set_opt("FontWeight", &self.font_weight);
+        keys.set_opt("ClipString", &self.clip_string);
+        keys.set_opt("ClipStringW", &self.clip_string_w);
+        keys.set_opt("ClipStringH", &self.clip_string_h);
+        keys.set_opt("StringCase", &self.string_case);
+        keys.set_opt("StringStyle", &self.string_style);
+        keys.set_opt("StringEffect", &self.string_effect);
+        keys.set_opt("FontEffectColor", &self.font_effect_color);
+        keys.set_opt("Prefix", &self.prefix);
+        keys.set_opt("Postfix", &self.postfix);
+        keys.set_opt("Angle", &self.angle);
+        for (i, setting) in self.inline_settings.iter().enumerate() {
+            keys.set(&format!("InlineSetting{}", index_suffix(i)), &setting.kind);
+            keys.set_opt(&format!("InlinePattern{}", index_suffix(i)), &setting.pattern);
         }
-        if let Some(x) = &self.clip_string_w {
-            ret += &format!("\nClipStringW={x}");
-        }
-        if self.anti_alias {
-            ret += &format!("\nAntiAlias=1");
-        }
-        write!(f, "{ret}")
+        keys.set_flag("AntiAlias", self.anti_alias);
     }
 }
 impl Default for StringOptions {
@@ -357,23 +666,166 @@ impl Default for StringOptions {
             text: String::new(),
             string_align: Some(StringAlign::CenterCenter),
             font_color: Some((255,255,255,255).into()),
+            font_face: None,
             font_size: Some(12_f64),
             font_weight: Some(800),
             clip_string: Some(2),
             clip_string_w: None,
+            clip_string_h: None,
+            string_case: None,
+            string_style: None,
+            string_effect: None,
+            font_effect_color: None,
+            prefix: None,
+            postfix: None,
+            angle: None,
+            inline_settings: Vec::new(),
             anti_alias: true,
         }
     }
 }
+impl StringOptions {
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+    pub fn with_align(mut self, align: StringAlign) -> Self {
+        self.string_align = Some(align);
+        self
+    }
+}
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 pub enum StringAlign {
+    LeftTop,
+    LeftCenter,
+    LeftBottom,
+    CenterTop,
     CenterCenter,
+    CenterBottom,
+    RightTop,
+    RightCenter,
+    RightBottom,
 }
 impl Display for StringAlign {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            StringAlign::LeftTop => write!(f, "LeftTop"),
+            StringAlign::LeftCenter => write!(f, "LeftCenter"),
+            StringAlign::LeftBottom => write!(f, "LeftBottom"),
+            StringAlign::CenterTop => write!(f, "CenterTop"),
             StringAlign::CenterCenter => write!(f, "CenterCenter"),
+            StringAlign::CenterBottom => write!(f, "CenterBottom"),
+            StringAlign::RightTop => write!(f, "RightTop"),
+            StringAlign::RightCenter => write!(f, "RightCenter"),
+            StringAlign::RightBottom => write!(f, "RightBottom"),
+        }
+    }
+}
+impl StringAlign {
+    // Where X/Y sit within the meter, in halves of its width and height
+    pub fn anchor(&self) -> (isize, isize) {
+        match self {
+            StringAlign::LeftTop => (0, 0),
+            StringAlign::LeftCenter => (0, 1),
+            StringAlign::LeftBottom => (0, 2),
+            StringAlign::CenterTop => (1, 0),
+            StringAlign::CenterCenter => (1, 1),
+            StringAlign::CenterBottom => (1, 2),
+            StringAlign::RightTop => (2, 0),
+            StringAlign::RightCenter => (2, 1),
+            StringAlign::RightBottom => (2, 2),
+        }
+    }
+}
+
+pub enum StringCase {
+    None,
+    Upper,
+    Lower,
+    Proper,
+}
+impl Display for StringCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringCase::None => write!(f, "None"),
+            StringCase::Upper => write!(f, "Upper"),
+            StringCase::Lower => write!(f, "Lower"),
+            StringCase::Proper => write!(f, "Proper"),
+        }
+    }
+}
+
+pub enum StringStyle {
+    Normal,
+    Bold,
+    Italic,
+    BoldItalic,
+}
+impl Display for StringStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringStyle::Normal => write!(f, "Normal"),
+            StringStyle::Bold => write!(f, "Bold"),
+            StringStyle::Italic => write!(f, "Italic"),
+            StringStyle::BoldItalic => write!(f, "BoldItalic"),
+        }
+    }
+}
+
+pub enum StringEffect {
+    None,
+    Shadow,
+    Border,
+}
+impl Display for StringEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringEffect::None => write!(f, "None"),
+            StringEffect::Shadow => write!(f, "Shadow"),
+            StringEffect::Border => write!(f, "Border"),
+        }
+    }
+}
+
+pub struct InlineSetting {
+    pub kind: InlineKind,
+    pub pattern: Option<String>,
+}
+
+pub enum InlineKind {
+    Face(String),
+    Size(f64),
+    Weight(usize),
+    Color(Color),
+    Italic,
+    Oblique,
+    Underline,
+    Strikethrough,
+    Case(StringCase),
+    CharacterSpacing(f64, f64),
+    Shadow(f64, f64, f64, Color),
+    Stroke(f64, Color),
+    GradientColor(f64, Vec<(Color, f64)>),
+    None,
+}
+impl Display for InlineKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InlineKind::Face(x) => write!(f, "Face | {x}"),
+            InlineKind::Size(x) => write!(f, "Size | {x}"),
+            InlineKind::Weight(x) => write!(f, "Weight | {x}"),
+            InlineKind::Color(x) => write!(f, "Color | {x}"),
+            InlineKind::Italic => write!(f, "Italic"),
+            InlineKind::Oblique => write!(f, "Oblique"),
+            InlineKind::Underline => write!(f, "Underline"),
+            InlineKind::Strikethrough => write!(f, "Strikethrough"),
+            InlineKind::Case(x) => write!(f, "Case | {x}"),
+            InlineKind::CharacterSpacing(lead, trail) => write!(f, "CharacterSpacing | {lead} | {trail}"),
+            InlineKind::Shadow(x, y, blur, c) => write!(f, "Shadow | {x} | {y} | {blur} | {c}"),
+            InlineKind::Stroke(w, c) => write!(f, "Stroke | {w} | {c}"),
+            InlineKind::GradientColor(angle, stops) => write!(f, "GradientColor | {angle} | {}", stops.iter().map(|(c, p)| format!("{c} ; {p}")).collect::<Vec<_>>().join(" | ")),
+            InlineKind::None => write!(f, "None"),
         }
     }
 }
@@ -381,13 +833,30 @@ impl Display for StringAlign {
 pub struct BarOptions {
     pub bar_color: Color,
     pub bar_orientation: BarOrientation,
+    pub bar_image: Option<String>,
+    pub bar_border: Option<isize>,
+    pub flip: bool,
 }
-impl Display for BarOptions {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "BarColor={}\nBarOrientation={}", self.bar_color, self.bar_orientation)
+impl BarOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        keys.set("BarColor", &self.bar_color);
+        keys.set("BarOrientation", &self.bar_orientation);
+        keys.set_opt("BarImage", &self.bar_image);
+        keys.set_opt("BarBorder", &self.bar_border);
+        keys.set_flag("Flip", self.flip);
+    }
+}
+impl BarOptions {
+    pub fn new(bar_color: Color, bar_orientation: BarOrientation) -> BarOptions {
+        BarOptions {
+            bar_color,
+            bar_orientation,
+            bar_image: None,
+            bar_border: None,
+            flip: false,
+        }
     }
 }
-#[allow(unused)]
 pub enum BarOrientation {
     Vertical,
     Horizontal,
@@ -400,3 +869,800 @@ impl Display for BarOrientation {
         }
     }
 }
+
+#[derive(Default)]
+pub struct ShapeOptions {
+    pub shapes: Vec<Shape>,
+}
+impl ShapeOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        for (i, shape) in self.shapes.iter().enumerate() {
+            keys.set(&format!("Shape{}", index_suffix(i)), shape);
+        }
+    }
+}
+impl ShapeOptions {
+    pub fn with_shape(mut self, shape: Shape) -> Self {
+        self.shapes.push(shape);
+        self
+    }
+}
+
+pub struct Shape {
+    pub kind: ShapeKind,
+    pub modifiers: Vec<ShapeModifier>,
+}
+impl Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)?;
+        for modifier in &self.modifiers {
+            write!(f, " | {modifier}")?;
+        }
+        Ok(())
+    }
+}
+impl Shape {
+    pub fn new(kind: ShapeKind) -> Shape {
+        Shape {
+            kind,
+            modifiers: Vec::new(),
+        }
+    }
+    pub fn rounded_rectangle(x: isize, y: isize, w: isize, h: isize, radius: isize) -> Shape {
+        Shape::new(ShapeKind::Rectangle(x, y, w, h, Some(radius)))
+    }
+    pub fn fill(mut self, color: Color) -> Self {
+        self.modifiers.push(ShapeModifier::FillColor(color));
+        self
+    }
+    pub fn stroke(mut self, color: Color, width: f64) -> Self {
+        self.modifiers.push(ShapeModifier::StrokeColor(color));
+        self.modifiers.push(ShapeModifier::StrokeWidth(width));
+        self
+    }
+    pub fn no_stroke(mut self) -> Self {
+        self.modifiers.push(ShapeModifier::StrokeWidth(0.0));
+        self
+    }
+}
+
+pub enum ShapeKind {
+    Rectangle(isize, isize, isize, isize, Option<isize>),
+    Ellipse(isize, isize, isize, isize),
+    Line(isize, isize, isize, isize),
+    Arc(isize, isize, isize, isize),
+    Path(String),
+    Combine(String, Vec<(CombineMode, String)>),
+}
+impl Display for ShapeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeKind::Rectangle(x, y, w, h, None) => write!(f, "Rectangle {x},{y},{w},{h}"),
+            ShapeKind::Rectangle(x, y, w, h, Some(r)) => write!(f, "Rectangle {x},{y},{w},{h},{r}"),
+            ShapeKind::Ellipse(x, y, rx, ry) => write!(f, "Ellipse {x},{y},{rx},{ry}"),
+            ShapeKind::Line(x1, y1, x2, y2) => write!(f, "Line {x1},{y1},{x2},{y2}"),
+            ShapeKind::Arc(x1, y1, x2, y2) => write!(f, "Arc {x1},{y1},{x2},{y2}"),
+            ShapeKind::Path(p) => write!(f, "Path {p}"),
+            ShapeKind::Combine(base, rest) => write!(f, "Combine {base}{}", rest.iter().map(|(m, s)| format!(" | {m} {s}")).collect::<String>()),
+        }
+    }
+}
+
+impl ShapeKind {
+    // Paths and combines reference other shapes so only plain geometry is scaled
+    pub fn scale(&mut self, factor: f64) {
+        let scale = |v: &mut isize| *v = (*v as f64 * factor).round() as isize;
+        match self {
+            ShapeKind::Rectangle(x, y, w, h, r) => {
+                for v in [x, y, w, h] {
+                    scale(v);
+                }
+                if let Some(r) = r {
+                    scale(r);
+                }
+            },
+            ShapeKind::Ellipse(a, b, c, d) | ShapeKind::Line(a, b, c, d) | ShapeKind::Arc(a, b, c, d) => {
+                for v in [a, b, c, d] {
+                    scale(v);
+                }
+            },
+            ShapeKind::Path(_) | ShapeKind::Combine(..) => {},
+        }
+    }
+}
+
+pub enum CombineMode {
+    Union,
+    Intersect,
+    Xor,
+    Exclude,
+}
+impl Display for CombineMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CombineMode::Union => write!(f, "Union"),
+            CombineMode::Intersect => write!(f, "Intersect"),
+            CombineMode::Xor => write!(f, "XOR"),
+            CombineMode::Exclude => write!(f, "Exclude"),
+        }
+    }
+}
+
+pub enum ShapeModifier {
+    FillColor(Color),
+    StrokeColor(Color),
+    StrokeWidth(f64),
+    Rotate(f64),
+    Offset(isize, isize),
+    Scale(f64, f64),
+}
+impl Display for ShapeModifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeModifier::FillColor(c) => write!(f, "Fill Color {c}"),
+            ShapeModifier::StrokeColor(c) => write!(f, "Stroke Color {c}"),
+            ShapeModifier::StrokeWidth(w) => write!(f, "StrokeWidth {w}"),
+            ShapeModifier::Rotate(a) => write!(f, "Rotate {a}"),
+            ShapeModifier::Offset(x, y) => write!(f, "Offset {x},{y}"),
+            ShapeModifier::Scale(x, y) => write!(f, "Scale {x},{y}"),
+        }
+    }
+}
+
+pub struct RotatorOptions {
+    pub image_name: String,
+    pub offset: (f64, f64),
+    pub start_angle: f64,
+    pub rotation_angle: f64,
+    pub value_remainder: Option<isize>,
+}
+impl RotatorOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        keys.set("ImageName", &self.image_name);
+        keys.set("OffsetX", self.offset.0);
+        keys.set("OffsetY", self.offset.1);
+        keys.set("StartAngle", self.start_angle);
+        keys.set("RotationAngle", self.rotation_angle);
+        keys.set_opt("ValueRemainder", &self.value_remainder);
+    }
+}
+
+pub struct RoundlineOptions {
+    pub line_length: f64,
+    pub line_start: f64,
+    pub line_width: f64,
+    pub line_color: Color,
+    pub start_angle: f64,
+    pub rotation_angle: f64,
+    pub solid: bool,
+}
+impl RoundlineOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        keys.set("LineLength", self.line_length);
+        keys.set("LineStart", self.line_start);
+        keys.set("LineWidth", self.line_width);
+        keys.set("LineColor", &self.line_color);
+        keys.set("StartAngle", self.start_angle);
+        keys.set("RotationAngle", self.rotation_angle);
+        keys.set_flag("Solid", self.solid);
+    }
+}
+
+pub struct ButtonOptions {
+    pub button_image: String,
+    pub button_command: Vec<String>,
+}
+impl ButtonOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        keys.set("ButtonImage", &self.button_image);
+        keys.set_actions("ButtonCommand", &self.button_command);
+    }
+}
+
+pub struct BitmapOptions {
+    pub bitmap_image: String,
+    pub bitmap_frames: usize,
+    pub bitmap_zero_frame: bool,
+    pub bitmap_extend: bool,
+    pub bitmap_digits: Option<usize>,
+    pub bitmap_separation: Option<isize>,
+}
+impl BitmapOptions {
+    fn write_keys(&self, keys: &mut Keys) {
+        keys.set("BitmapImage", &self.bitmap_image);
+        keys.set("BitmapFrames", self.bitmap_frames);
+        keys.set_flag("BitmapZeroFrame", self.bitmap_zero_frame);
+        keys.set_flag("BitmapExtend", self.bitmap_extend);
+        keys.set_opt("BitmapDigits", &self.bitmap_digits);
+        keys.set_opt("BitmapSeparation", &self.bitmap_separation);
+    }
+}
+
+// Reading skins back
+
+// Sections of an ini file in order, comments and blank lines dropped
+pub fn parse_ini(contents: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut ret: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for line in contents.lines().map(|l| l.trim()) {
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            ret.push((line[1..line.len() - 1].to_string(), Vec::new()));
+            continue;
+        }
+        if let (Some((_, keys)), Some((key, value))) = (ret.last_mut(), line.split_once('=')) {
+            keys.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    ret
+}
+
+pub fn read_skin(skin_path: &str) -> Result<Vec<RmObject>, String> {
+    std::fs::read_to_string(skin_path)
+        .map(|contents| parse_ini(&contents).into_iter().map(|(name, keys)| RmObject::from_keys(&name, keys)).collect())
+        .map_err(|e| format!("Failed To Read Skin: {e:?}"))
+}
+
+// Options of one section. Writing sets them in order, reading matches them without
+// case like Rainmeter does and leaves whatever isn't taken (or doesn't parse) for `other`
+struct Keys(Vec<(String, String)>);
+impl Keys {
+    fn set(&mut self, key: &str, value: impl Display) {
+        self.0.push((key.to_string(), value.to_string()));
+    }
+    fn set_opt(&mut self, key: &str, value: &Option<impl Display>) {
+        if let Some(v) = value {
+            self.set(key, v);
+        }
+    }
+    fn set_flag(&mut self, key: &str, value: bool) {
+        if value {
+            self.set(key, 1);
+        }
+    }
+    fn set_actions(&mut self, key: &str, actions: &[String]) {
+        if !actions.is_empty() {
+            self.set(key, actions.iter().map(|a| format!("[{a}]")).collect::<String>());
+        }
+    }
+    fn take(&mut self, key: &str) -> Option<String> {
+        self.0.iter().position(|(k, _)| k.eq_ignore_ascii_case(key)).map(|i| self.0.remove(i).1)
+    }
+    fn take_parse<T: std::str::FromStr>(&mut self, key: &str) -> Option<T> {
+        let i = self.0.iter().position(|(k, _)| k.eq_ignore_ascii_case(key))?;
+        let ret = self.0[i].1.parse::<T>().ok()?;
+        self.0.remove(i);
+        Some(ret)
+    }
+    fn take_named<T: Display>(&mut self, key: &str, variants: Vec<T>) -> Option<T> {
+        let i = self.0.iter().position(|(k, _)| k.eq_ignore_ascii_case(key))?;
+        let value = self.0[i].1.clone();
+        let ret = variants.into_iter().find(|v| v.to_string().eq_ignore_ascii_case(&value))?;
+        self.0.remove(i);
+        Some(ret)
+    }
+    fn take_flag(&mut self, key: &str) -> bool {
+        self.take_parse::<isize>(key).is_some_and(|v| v != 0)
+    }
+    fn take_actions(&mut self, key: &str) -> Vec<String> {
+        self.take(key).map(|v| split_actions(&v)).unwrap_or_default()
+    }
+    fn take_list(&mut self, key: &str) -> Vec<String> {
+        self.take(key).map(|v| v.split('|').map(|g| g.trim().to_string()).collect()).unwrap_or_default()
+    }
+    fn take_rect(&mut self, key: &str) -> Option<(isize, isize, isize, isize)> {
+        let i = self.0.iter().position(|(k, _)| k.eq_ignore_ascii_case(key))?;
+        let parts = self.0[i].1.split(',').map(|p| p.trim().parse::<isize>()).collect::<Result<Vec<_>, _>>().ok()?;
+        let ret = match parts[..] {
+            [a, b, c, d] => (a, b, c, d),
+            _ => return None,
+        };
+        self.0.remove(i);
+        Some(ret)
+    }
+}
+
+// `[!A "x"][!B "[Measure]"]` into its bangs, brackets inside a bang are kept
+fn split_actions(value: &str) -> Vec<String> {
+    let mut ret = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in value.chars() {
+        match c {
+            '[' if depth == 0 => depth = 1,
+            ']' if depth == 1 => {
+                depth = 0;
+                ret.push(std::mem::take(&mut current));
+            },
+            _ if depth == 0 => {},
+            _ => {
+                depth += match c {'[' => 1, ']' => -1, _ => 0};
+                current.push(c);
+            },
+        }
+    }
+    if ret.is_empty() && !value.trim().is_empty() {
+        ret.push(value.trim().to_string());
+    }
+    ret
+}
+
+fn split_word(s: &str) -> (&str, &str) {
+    s.split_once(char::is_whitespace).map(|(a, b)| (a, b.trim())).unwrap_or((s, ""))
+}
+
+fn parse_list<T: std::str::FromStr>(s: &str) -> Result<Vec<T>, String> {
+    s.split(',').map(|p| p.trim().parse::<T>().map_err(|_| format!("{s}: Not A Number"))).collect()
+}
+
+// `Rectangle 0,0,10,10,4 | Fill Color 0,0,0,255 | StrokeWidth 0` as written in a Shape option
+impl std::str::FromStr for Shape {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('|').map(|p| p.trim());
+        let (name, params) = split_word(parts.next().unwrap_or_default());
+        let unknown = || format!("{s}: Unknown Shape");
+        let kind = match (name.to_lowercase().as_str(), params) {
+            ("rectangle", p) => match parse_list(p)?[..] {
+                [x, y, w, h] => ShapeKind::Rectangle(x, y, w, h, None),
+                [x, y, w, h, r] => ShapeKind::Rectangle(x, y, w, h, Some(r)),
+                _ => return Err(unknown()),
+            },
+            ("ellipse", p) => match parse_list(p)?[..] {
+                [x, y, r] => ShapeKind::Ellipse(x, y, r, r),
+                [x, y, rx, ry] => ShapeKind::Ellipse(x, y, rx, ry),
+                _ => return Err(unknown()),
+            },
+            ("line", p) => match parse_list(p)?[..] {
+                [x1, y1, x2, y2] => ShapeKind::Line(x1, y1, x2, y2),
+                _ => return Err(unknown()),
+            },
+            ("arc", p) => match parse_list(p)?[..] {
+                [x1, y1, x2, y2] => ShapeKind::Arc(x1, y1, x2, y2),
+                _ => return Err(unknown()),
+            },
+            ("path", p) if !p.is_empty() => ShapeKind::Path(p.to_string()),
+            ("combine", p) if !p.is_empty() => ShapeKind::Combine(p.to_string(), Vec::new()),
+            _ => return Err(unknown()),
+        };
+        let mut ret = Shape::new(kind);
+        for part in parts {
+            let (name, params) = split_word(part);
+            let mode = match name.to_lowercase().as_str() {
+                "union" => Some(CombineMode::Union),
+                "intersect" => Some(CombineMode::Intersect),
+                "xor" => Some(CombineMode::Xor),
+                "exclude" => Some(CombineMode::Exclude),
+                _ => None,
+            };
+            if let (Some(mode), ShapeKind::Combine(_, rest)) = (mode, &mut ret.kind) {
+                rest.push((mode, params.to_string()));
+                continue;
+            }
+            let color = |p: &str| match split_word(p) {
+                (what, color) if what.eq_ignore_ascii_case("Color") => color.parse::<Color>(),
+                _ => Err(format!("{s}: Unknown Modifier")),
+            };
+            ret.modifiers.push(match name.to_lowercase().as_str() {
+                "fill" => ShapeModifier::FillColor(color(params)?),
+                "stroke" => ShapeModifier::StrokeColor(color(params)?),
+                "strokewidth" => match parse_list(params)?[..] {
+                    [w] => ShapeModifier::StrokeWidth(w),
+                    _ => return Err(format!("{s}: Unknown Modifier")),
+                },
+                "rotate" => match parse_list(params)?[..] {
+                    [a] => ShapeModifier::Rotate(a),
+                    _ => return Err(format!("{s}: Unknown Modifier")),
+                },
+                "offset" => match parse_list(params)?[..] {
+                    [x, y] => ShapeModifier::Offset(x, y),
+                    _ => return Err(format!("{s}: Unknown Modifier")),
+                },
+                "scale" => match parse_list(params)?[..] {
+                    [x, y] => ShapeModifier::Scale(x, y),
+                    _ => return Err(format!("{s}: Unknown Modifier")),
+                },
+                _ => return Err(format!("{s}: Unknown Modifier")),
+            });
+        }
+        Ok(ret)
+    }
+}
+
+// `Shadow | 1 | 1 | 2 | 0,0,0,255` as written in an InlineSetting option
+impl std::str::FromStr for InlineKind {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split('|').map(|p| p.trim()).collect();
+        let number = |p: &str| p.parse::<f64>().map_err(|_| format!("{s}: Not A Number"));
+        let unknown = || format!("{s}: Unknown Inline Setting");
+        Ok(match (parts[0].to_lowercase().as_str(), &parts[1..]) {
+            ("face", [face]) => InlineKind::Face(face.to_string()),
+            ("size", [size]) => InlineKind::Size(number(size)?),
+            ("weight", [weight]) => InlineKind::Weight(weight.parse().map_err(|_| unknown())?),
+            ("color", [color]) => InlineKind::Color(color.parse()?),
+            ("italic", []) => InlineKind::Italic,
+            ("oblique", []) => InlineKind::Oblique,
+            ("underline", []) => InlineKind::Underline,
+            ("strikethrough", []) => InlineKind::Strikethrough,
+            ("case", [case]) => InlineKind::Case(match case.to_lowercase().as_str() {
+                "none" => StringCase::None,
+                "upper" => StringCase::Upper,
+                "lower" => StringCase::Lower,
+                "proper" => StringCase::Proper,
+                _ => return Err(unknown()),
+            }),
+            ("characterspacing", [lead, trail]) => InlineKind::CharacterSpacing(number(lead)?, number(trail)?),
+            ("shadow", [x, y, blur, color]) => InlineKind::Shadow(number(x)?, number(y)?, number(blur)?, color.parse()?),
+            ("stroke", [width, color]) => InlineKind::Stroke(number(width)?, color.parse()?),
+            ("gradientcolor", [angle, stops @ ..]) if !stops.is_empty() => InlineKind::GradientColor(
+                number(angle)?,
+                stops.iter().map(|stop| match stop.split_once(';') {
+                    Some((color, offset)) => Ok((color.trim().parse()?, number(offset.trim())?)),
+                    None => Err(unknown()),
+                }).collect::<Result<Vec<_>, String>>()?,
+            ),
+            ("none", []) => InlineKind::None,
+            _ => return Err(unknown()),
+        })
+    }
+}
+
+impl RmObject {
+    pub fn from_keys(name: &str, keys: Vec<(String, String)>) -> RmObject {
+        let mut keys = Keys(keys);
+        let object_type = if name.eq_ignore_ascii_case("Variables") {
+            ObjectType::Variables(keys.0)
+        } else if let Some(t) = keys.take("Meter") {
+            let t = MeterType::from_keys(&t, &mut keys);
+            ObjectType::meter(t, MeterOptions::from_keys(keys))
+        } else if let Some(t) = keys.take("Measure") {
+            let t = MeasureType::from_keys(&t, &mut keys);
+            ObjectType::Measure(t, MeasureOptions::from_keys(keys))
+        } else {
+            ObjectType::Section(keys.0)
+        };
+        RmObject {
+            name: name.to_string(),
+            object_type,
+        }
+    }
+    // Options as they would be written to the skin
+    pub fn keys(&self) -> Vec<(String, String)> {
+        let mut keys = Keys(Vec::new());
+        match &self.object_type {
+            ObjectType::Measure(t, o) => {
+                t.write_keys(&mut keys);
+                o.write_keys(&mut keys);
+            },
+            ObjectType::Meter(t, o) => {
+                t.write_keys(&mut keys);
+                o.write_keys(&mut keys);
+            },
+            ObjectType::Variables(v) | ObjectType::Section(v) => keys.0.extend(v.iter().cloned()),
+        }
+        keys.0
+    }
+}
+
+impl MeterType {
+    fn from_keys(t: &str, keys: &mut Keys) -> MeterType {
+        match t.to_lowercase().as_str() {
+            "image" => MeterType::Image(ImageOptions {
+                image_name: keys.take("ImageName").unwrap_or_default(),
+                preseve_aspect_ratio: keys.take_flag("PreserveAspectRatio"),
+                image_alpha: keys.take_parse("ImageAlpha"),
+                image_tint: keys.take_parse("ImageTint"),
+                greyscale: keys.take_flag("Greyscale"),
+                image_crop: keys.take_rect("ImageCrop"),
+                image_rotate: keys.take_parse("ImageRotate"),
+                image_flip: keys.take_named("ImageFlip", vec![ImageFlip::Horizontal, ImageFlip::Vertical, ImageFlip::Both]),
+                tile: keys.take_flag("Tile"),
+            }),
+            "string" => MeterType::String(StringOptions {
+                text: keys.take("Text").unwrap_or_default(),
+                string_align: keys.take_named("StringAlign", vec![
+                    StringAlign::LeftTop, StringAlign::LeftCenter, StringAlign::LeftBottom,
+                    StringAlign::CenterTop, StringAlign::CenterCenter, StringAlign::CenterBottom,
+                    StringAlign::RightTop, StringAlign::RightCenter, StringAlign::RightBottom,
+                ]),
+                font_color: keys.take_parse("FontColor"),
+                font_face: keys.take("FontFace"),
+                font_size: keys.take_parse("FontSize"),
+                font_weight: keys.take_parse("FontWeight"),
+                clip_string: keys.take_parse("ClipString"),
+                clip_string_w: keys.take_parse("ClipStringW"),
+                clip_string_h: keys.take_parse("ClipStringH"),
+                string_case: keys.take_named("StringCase", vec![StringCase::None, StringCase::Upper, StringCase::Lower, StringCase::Proper]),
+                string_style: keys.take_named("StringStyle", vec![StringStyle::Normal, StringStyle::Bold, StringStyle::Italic, StringStyle::BoldItalic]),
+                string_effect: keys.take_named("StringEffect", vec![StringEffect::None, StringEffect::Shadow, StringEffect::Border]),
+                font_effect_color: keys.take_parse("FontEffectColor"),
+                prefix: keys.take("Prefix"),
+                postfix: keys.take("Postfix"),
+                angle: keys.take_parse("Angle"),
+                inline_settings: {
+                    // Numbered like shapes, kept as written from the first one that doesn't parse
+                    let mut settings = Vec::new();
+                    while let Some(kind) = keys.take_parse::<InlineKind>(&format!("InlineSetting{}", index_suffix(settings.len()))) {
+                        let pattern = keys.take(&format!("InlinePattern{}", index_suffix(settings.len())));
+                        settings.push(InlineSetting { kind, pattern });
+                    }
+                    settings
+                },
+                anti_alias: keys.take_flag("AntiAlias"),
+            }),
+            "bar" => MeterType::Bar(BarOptions {
+                bar_color: keys.take_parse("BarColor").unwrap_or((0,0,0,255).into()),
+                bar_orientation: keys.take_named("BarOrientation", vec![BarOrientation::Vertical, BarOrientation::Horizontal]).unwrap_or(BarOrientation::Vertical),
+                bar_image: keys.take("BarImage"),
+                bar_border: keys.take_parse("BarBorder"),
+                flip: keys.take_flag("Flip"),
+            }),
+            "shape" => {
+                // Shapes are numbered, the first one that doesn't parse and all after it stay as written
+                let mut shapes = Vec::new();
+                while let Some(shape) = keys.take_parse::<Shape>(&format!("Shape{}", index_suffix(shapes.len()))) {
+                    shapes.push(shape);
+                }
+                MeterType::Shape(ShapeOptions { shapes })
+            },
+            "rotator" => MeterType::Rotator(RotatorOptions {
+                image_name: keys.take("ImageName").unwrap_or_default(),
+                offset: (keys.take_parse("OffsetX").unwrap_or(0.0), keys.take_parse("OffsetY").unwrap_or(0.0)),
+                start_angle: keys.take_parse("StartAngle").unwrap_or(0.0),
+                rotation_angle: keys.take_parse("RotationAngle").unwrap_or(std::f64::consts::TAU),
+                value_remainder: keys.take_parse("ValueRemainder"),
+            }),
+            "roundline" => MeterType::Roundline(RoundlineOptions {
+                line_length: keys.take_parse("LineLength").unwrap_or(0.0),
+                line_start: keys.take_parse("LineStart").unwrap_or(0.0),
+                line_width: keys.take_parse("LineWidth").unwrap_or(1.0),
+                line_color: keys.take_parse("LineColor").unwrap_or((0,0,0,255).into()),
+                start_angle: keys.take_parse("StartAngle").unwrap_or(0.0),
+                rotation_angle: keys.take_parse("RotationAngle").unwrap_or(std::f64::consts::TAU),
+                solid: keys.take_flag("Solid"),
+            }),
+            "button" => MeterType::Button(ButtonOptions {
+                button_image: keys.take("ButtonImage").unwrap_or_default(),
+                button_command: keys.take_actions("ButtonCommand"),
+            }),
+            "bitmap" => MeterType::Bitmap(BitmapOptions {
+                bitmap_image: keys.take("BitmapImage").unwrap_or_default(),
+                bitmap_frames: keys.take_parse("BitmapFrames").unwrap_or(1),
+                bitmap_zero_frame: keys.take_flag("BitmapZeroFrame"),
+                bitmap_extend: keys.take_flag("BitmapExtend"),
+                bitmap_digits: keys.take_parse("BitmapDigits"),
+                bitmap_separation: keys.take_parse("BitmapSeparation"),
+            }),
+            _ => MeterType::Other(t.to_string()),
+        }
+    }
+}
+
+impl MeterOptions {
+    fn from_keys(mut keys: Keys) -> MeterOptions {
+        let mut ret = MeterOptions::new();
+        ret.pos = (keys.take_parse("X").unwrap_or(0), keys.take_parse("Y").unwrap_or(0)).into();
+        ret.size = (keys.take_parse("W").unwrap_or(0), keys.take_parse("H").unwrap_or(0)).into();
+        ret.padding = keys.take_rect("Padding");
+        ret.solid_color = keys.take_parse("SolidColor");
+        ret.solid_color2 = keys.take_parse("SolidColor2");
+        ret.gradient_angle = keys.take_parse("GradientAngle");
+        ret.bevel_type = keys.take_named("BevelType", vec![BevelType::None, BevelType::Raised, BevelType::Sunken]);
+        ret.measure_name = keys.take("MeasureName");
+        ret.groups = keys.take_list("Group");
+        ret.container = keys.take("Container");
+        ret.left_click_action = keys.take_actions("LeftMouseUpAction");
+        ret.left_mouse_down_action = keys.take_actions("LeftMouseDownAction");
+        ret.left_double_click_action = keys.take_actions("LeftMouseDoubleClickAction");
+        ret.right_click_action = keys.take_actions("RightMouseUpAction");
+        ret.middle_click_action = keys.take_actions("MiddleMouseUpAction");
+        ret.scroll_down_action = keys.take_actions("MouseScrollDownAction");
+        ret.scroll_up_action = keys.take_actions("MouseScrollUpAction");
+        ret.mouse_over_action = keys.take_actions("MouseOverAction");
+        ret.mouse_leave_action = keys.take_actions("MouseLeaveAction");
+        ret.mouse_action_cursor = keys.take_parse::<isize>("MouseActionCursor").map(|v| v != 0);
+        ret.tool_tip = keys.take("ToolTipText").map(|text| {
+            let mut tool_tip = ToolTip::new(text);
+            tool_tip.title = keys.take("ToolTipTitle");
+            tool_tip.icon = keys.take("ToolTipIcon").map(|icon| match icon.to_lowercase().as_str() {
+                "info" => ToolTipIcon::Info,
+                "warning" => ToolTipIcon::Warning,
+                "error" => ToolTipIcon::Error,
+                "question" => ToolTipIcon::Question,
+                "shield" => ToolTipIcon::Shield,
+                _ => ToolTipIcon::Custom(icon),
+            });
+            tool_tip.width = keys.take_parse("ToolTipWidth");
+            tool_tip.balloon = keys.take_flag("ToolTipType");
+            tool_tip
+        });
+        ret.update_divider = keys.take_parse("UpdateDivider");
+        ret.anti_alias = keys.take_flag("AntiAlias");
+        ret.dynamic_variables = keys.take_flag("DynamicVariables");
+        ret.hidden = keys.take_flag("Hidden");
+        ret.other = keys.0;
+        ret
+    }
+}
+
+impl MeasureType {
+    fn from_keys(t: &str, keys: &mut Keys) -> MeasureType {
+        match t.to_lowercase().as_str() {
+            "plugin" if keys.0.iter().any(|(k, v)| k.eq_ignore_ascii_case("Plugin") && v.ends_with("Splatink")) => {
+                match keys.take("Type").unwrap_or_default().to_lowercase().as_str() {
+                    "timebar" => {
+                        keys.take("Plugin");
+                        MeasureType::Plugin(PluginType::Splatink(SplatinkType::TimeBar(TimeBarOptions {
+                            start_time: keys.take_parse("StartTime").unwrap_or(0),
+                            end_time: keys.take_parse("EndTime").unwrap_or(0),
+                            variable: None,
+                        })))
+                    },
+                    "core" => {
+                        keys.take("Plugin");
+                        MeasureType::Plugin(PluginType::Splatink(SplatinkType::Core(keys.take("Sche").unwrap_or_default())))
+                    },
+                    other => {
+                        keys.0.push(("Type".to_string(), other.to_string()));
+                        MeasureType::Other(t.to_string())
+                    },
+                }
+            },
+            "string" => MeasureType::String(keys.take("String").unwrap_or_default()),
+            "calc" => MeasureType::Calc(keys.take("Formula").unwrap_or_default()),
+            "time" => MeasureType::Time(TimeOptions {
+                format: keys.take("Format"),
+                time_stamp: keys.take_parse("TimeStamp").inspect(|_| {keys.take("TimeStampFormat");}),
+                time_zone: keys.take_parse("TimeZone"),
+            }),
+            _ => MeasureType::Other(t.to_string()),
+        }
+    }
+}
+
+impl MeasureOptions {
+    fn from_keys(mut keys: Keys) -> MeasureOptions {
+        let mut if_conditions = Vec::new();
+        while let Some(condition) = keys.take(&format!("IfCondition{}", index_suffix(if_conditions.len()))) {
+            let suffix = index_suffix(if_conditions.len());
+            if_conditions.push(IfCondition {
+                condition,
+                true_action: keys.take_actions(&format!("IfTrueAction{suffix}")),
+                false_action: keys.take_actions(&format!("IfFalseAction{suffix}")),
+            });
+        }
+        MeasureOptions {
+            on_change_action: keys.take_actions("OnChangeAction"),
+            on_update_action: keys.take_actions("OnUpdateAction"),
+            dynamic_variables: keys.take_flag("DynamicVariables"),
+            update_divider: keys.take_parse("UpdateDivider"),
+            disabled: keys.take_flag("Disabled"),
+            paused: keys.take_flag("Paused"),
+            groups: keys.take_list("Group"),
+            min_value: keys.take_parse("MinValue"),
+            max_value: keys.take_parse("MaxValue"),
+            if_conditions,
+            // Substitute pairs are quoted and may contain commas so they stay as written
+            substitute: Vec::new(),
+            regexp_substitute: keys.take_flag("RegExpSubstitute"),
+            other: keys.0,
+        }
+    }
+}
+
+// Applies the sections of `overrides` on top of `base`, replacing matching options
+// and appending new options and sections
+pub fn merge_skin(base: Vec<RmObject>, overrides: Vec<RmObject>) -> Vec<RmObject> {
+    let mut ret = base;
+    for over in overrides {
+        match ret.iter().position(|o| o.name.eq_ignore_ascii_case(&over.name)) {
+            Some(i) => {
+                let mut keys = ret[i].keys();
+                for (key, value) in over.keys() {
+                    match keys.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(&key)) {
+                        Some(existing) => existing.1 = value,
+                        None => keys.push((key, value)),
+                    }
+                }
+                ret[i] = RmObject::from_keys(&over.name, keys);
+            },
+            None => ret.push(over),
+        }
+    }
+    ret
+}
+
+// Problems Rainmeter would only report once the skin is loaded
+pub fn validate_skin<'a>(objects: impl IntoIterator<Item = &'a RmObject>) -> Vec<String> {
+    let objects: Vec<&RmObject> = objects.into_iter().collect();
+    let mut ret = Vec::new();
+    let find = |name: &str| objects.iter().find(|o| o.name.eq_ignore_ascii_case(name));
+    for (i, obj) in objects.iter().enumerate() {
+        if objects[..i].iter().any(|o| o.name.eq_ignore_ascii_case(&obj.name)) {
+            ret.push(format!("Duplicate Section: {}", obj.name));
+        }
+        if let ObjectType::Meter(_, ref o) = obj.object_type {
+            // Names built from variables or section variables can't be checked here
+            let dynamic = |n: &str| n.contains('#') || n.contains('[');
+            if let Some(m) = o.measure_name.as_ref().filter(|m| !dynamic(m)) {
+                if !matches!(find(m).map(|o| &o.object_type), Some(ObjectType::Measure(..))) {
+                    ret.push(format!("{}: MeasureName {m} Is Not A Measure", obj.name));
+                }
+            }
+            if let Some(c) = o.container.as_ref().filter(|c| !dynamic(c)) {
+                if !matches!(find(c).map(|o| &o.object_type), Some(ObjectType::Meter(..))) {
+                    ret.push(format!("{}: Container {c} Is Not A Meter", obj.name));
+                }
+            }
+        }
+    }
+    ret
+}
+
+#[derive(Default)]
+pub struct SkinDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    // Section and the option names that differ
+    pub changed: Vec<(String, Vec<String>)>,
+}
+impl SkinDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+    // Whether every difference is inside `section`, e.g. only variable values changed
+    pub fn only_in(&self, section: &str) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.iter().all(|(name, _)| name.eq_ignore_ascii_case(section))
+    }
+}
+impl Display for SkinDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} Added, {} Removed, {} Changed", self.added.len(), self.removed.len(), self.changed.len())
+    }
+}
+
+pub fn diff_skins(old: &[RmObject], new: &[RmObject]) -> SkinDiff {
+    let mut ret = SkinDiff::default();
+    let normalized = |o: &RmObject| {
+        let mut keys: Vec<(String, String)> = o.keys().into_iter().map(|(k, v)| (k.to_lowercase(), v)).collect();
+        keys.sort();
+        keys
+    };
+    for n in new {
+        match old.iter().find(|o| o.name.eq_ignore_ascii_case(&n.name)) {
+            Some(o) => {
+                let (old_keys, new_keys) = (normalized(o), normalized(n));
+                let mut changed: Vec<String> = old_keys.iter().filter(|k| !new_keys.contains(k))
+                    .chain(new_keys.iter().filter(|k| !old_keys.contains(k)))
+                    .map(|(k, _)| k.clone())
+                    .collect();
+                changed.sort();
+                changed.dedup();
+                if !changed.is_empty() {
+                    ret.changed.push((n.name.clone(), changed));
+                }
+            },
+            None => ret.added.push(n.name.clone()),
+        }
+    }
+    for o in old {
+        if !new.iter().any(|n| n.name.eq_ignore_ascii_case(&o.name)) {
+            ret.removed.push(o.name.clone());
+        }
+    }
+    ret
+}
+
+// Variables whose value differs between the [Variables] sections of two skins
+pub fn changed_variables(old: &[RmObject], new: &[RmObject]) -> Vec<(String, String)> {
+    let variables = |objects: &[RmObject]| objects.iter()
+        .find(|o| o.name.eq_ignore_ascii_case("Variables"))
+        .map(|o| o.keys())
+        .unwrap_or_default();
+    let old = variables(old);
+    variables(new).into_iter()
+        .filter(|(k, v)| !old.iter().any(|(ok, ov)| ok.eq_ignore_ascii_case(k) && ov == v))
+        .collect()
+}
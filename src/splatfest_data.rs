@@ -18,6 +18,17 @@ pub struct SplatfestData {
     pub AP: region,
 }
 
+impl SplatfestData {
+    pub fn region(&self, name: &str) -> &region {
+        match name.to_uppercase().as_str() {
+            "EU" => &self.EU,
+            "JP" => &self.JP,
+            "AP" => &self.AP,
+            _ => &self.US,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct region {
     pub data: data,
@@ -0,0 +1,118 @@
+extern crate chrono;
+extern crate serde;
+use std::collections::BTreeMap;
+use self::chrono::{Duration, Local};
+use self::serde::Serialize;
+use crate::{history::History, rm_structure::{format_duration, Query, Schedule, StatsEvent}};
+
+pub const STATS_FILE_NAME: &str = "Splatoon 3 Stats.json";
+// Lines shown per block of the Stats panel, the export has everything
+const PANEL_LINES: usize = 5;
+
+#[derive(Serialize)]
+pub struct StageRule {
+    pub rule: String,
+    pub stage: String,
+    pub appearances: usize,
+    // Between the starts of one appearance and the next, rotations on at the same time count once
+    pub average_gap_hours: Option<f64>,
+}
+
+// Counts over the rotation history, for the Stats panel and `Export stats`
+#[derive(Serialize, Default)]
+pub struct Stats {
+    pub days: i64,
+    pub rotations: usize,
+    // Rule -> stage -> appearances, over every battle schedule
+    pub stages_by_rule: BTreeMap<String, BTreeMap<String, usize>>,
+    pub stage_rules: Vec<StageRule>,
+    pub coop_stages: BTreeMap<String, usize>,
+    pub kings: BTreeMap<String, usize>,
+    // `Random`, `Rare` or `None` -> Salmon Run rotations
+    pub random_weapons: BTreeMap<String, usize>,
+}
+
+impl Stats {
+    pub fn compute(history: &History, days: i64) -> Stats {
+        let mut ret = Stats { days, ..Stats::default() };
+        let mut starts: BTreeMap<(String, String), Vec<i64>> = BTreeMap::new();
+        let since = (Local::now() - Duration::days(days)).timestamp();
+        for entry in history.entries.iter().filter(|e| e.start >= since) {
+            ret.rotations += 1;
+            if let Some(rule) = entry.query("rule") {
+                for stage in ["stage1", "stage2"].iter().filter_map(|f| entry.query(f)) {
+                    *ret.stages_by_rule.entry(rule.clone()).or_default().entry(stage.clone()).or_default() += 1;
+                    starts.entry((rule.clone(), stage)).or_default().push(entry.start);
+                }
+            } else if let Some(king) = entry.query("king") {
+                *ret.kings.entry(king).or_default() += 1;
+                if let Some(stage) = entry.query("stage") {
+                    *ret.coop_stages.entry(stage).or_default() += 1;
+                }
+                if let Some(random) = entry.query("random") {
+                    *ret.random_weapons.entry(random).or_default() += 1;
+                }
+            }
+        }
+        for ((rule, stage), mut times) in starts {
+            let appearances = times.len();
+            times.sort();
+            times.dedup();
+            let average_gap_hours = (times.len() > 1).then(|| {
+                (times[times.len() - 1] - times[0]) as f64 / (times.len() - 1) as f64 / 3600.0
+            });
+            ret.stage_rules.push(StageRule { rule, stage, appearances, average_gap_hours });
+        }
+        ret
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed To Serialize: {e:?}"))
+    }
+
+    // Most common first, ties in name order
+    fn top(counts: &BTreeMap<String, usize>) -> Vec<(&String, usize)> {
+        let mut ret: Vec<(&String, usize)> = counts.iter().map(|(k, v)| (k, *v)).collect();
+        ret.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        ret
+    }
+
+    // The Stats panel, navigated to like the other schedules
+    pub fn to_schedule(&self) -> Schedule<StatsEvent> {
+        let mut events = vec![StatsEvent {
+            title: format!("Last {} days", self.days),
+            lines: vec![("Rotations seen".to_string(), self.rotations.to_string())],
+        }];
+        for (rule, stages) in self.stages_by_rule.iter() {
+            events.push(StatsEvent {
+                title: rule.clone(),
+                lines: Self::top(stages).into_iter().take(PANEL_LINES).map(|(stage, count)| {
+                    let gap = self.stage_rules.iter()
+                        .find(|s| s.rule == *rule && s.stage == *stage)
+                        .and_then(|s| s.average_gap_hours)
+                        .map(|h| format!(", every {}", format_duration(Duration::minutes((h * 60.0) as i64))))
+                        .unwrap_or_default();
+                    (stage.clone(), format!("{count}x{gap}"))
+                }).collect(),
+            });
+        }
+        let coop_total: usize = self.kings.values().sum();
+        let percents = |counts: &BTreeMap<String, usize>| Self::top(counts).into_iter()
+            .take(PANEL_LINES)
+            .map(|(name, count)| (name.clone(), format!("{count}x, {:.0}%", count as f64 * 100.0 / coop_total.max(1) as f64)))
+            .collect::<Vec<_>>();
+        for (title, counts) in [("Salmon Run Stages", &self.coop_stages), ("King Salmonids", &self.kings), ("Random Weapons", &self.random_weapons)] {
+            if !counts.is_empty() {
+                events.push(StatsEvent { title: title.to_string(), lines: percents(counts) });
+            }
+        }
+        Schedule::<StatsEvent> {
+            title: "Stats".to_string(),
+            id: "StatsSche".to_string(),
+            prev_sche: String::new(),
+            next_sche: String::new(),
+            history: Vec::new(),
+            events,
+        }
+    }
+}
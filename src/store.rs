@@ -0,0 +1,90 @@
+extern crate chrono;
+extern crate reqwest;
+use std::sync::{Arc, Mutex, MutexGuard};
+use self::chrono::{DateTime, Duration, Local};
+use self::reqwest::blocking::{ClientBuilder, Client};
+use crate::{github_data::Releases, schedule_data::RotationData, splatfest_data::SplatfestData};
+
+// Every plugin instance in the process shares one copy of the schedule data and one
+// web client, so the feed is only pulled and parsed once however many skins use it
+pub struct Store {
+    refs: usize,
+    pub client: Option<Client>,
+    pub schedules: Option<Arc<RotationData>>,
+    // Bumped whenever the schedules are replaced, instances compare it to know when to rewrite
+    pub version: u64,
+    // Pulled by whichever instance rewrites first after the schedules change
+    pub splatfests: Option<Arc<SplatfestData>>,
+    pub releases: Option<Arc<Releases>>,
+    web_pull_cooldown_until: DateTime<Local>,
+    pub web_pull_cooldown_set: u32,
+    // Pull on the next check even if the data isn't out of date, until a pull goes through
+    pub repull_requested: bool,
+}
+
+static STORE: Mutex<Option<Store>> = Mutex::new(None);
+
+impl Store {
+    fn new() -> Store {
+        Store {
+            refs: 0,
+            client: ClientBuilder::new().user_agent("Splatoon-3-Rotation-Display").build().ok(),
+            schedules: None,
+            version: 0,
+            splatfests: None,
+            releases: None,
+            web_pull_cooldown_until: Local::now(),
+            web_pull_cooldown_set: 2,
+            repull_requested: false,
+        }
+    }
+    pub fn set_schedules(&mut self, schedules: RotationData) -> (Option<Arc<RotationData>>, u64) {
+        self.schedules = Some(Arc::new(schedules));
+        self.version += 1;
+        self.splatfests = None;
+        self.releases = None;
+        (self.schedules.clone(), self.version)
+    }
+    pub fn web_pull_cooldown(&self) -> Duration {
+        (self.web_pull_cooldown_until - Local::now()).max(Duration::zero())
+    }
+    // Doubles every failed pull, up to about 17 minutes
+    pub fn start_web_pull_cooldown(&mut self) -> Duration {
+        let cooldown = Duration::seconds(2_i64.pow(self.web_pull_cooldown_set));
+        self.web_pull_cooldown_until = Local::now() + cooldown;
+        self.web_pull_cooldown_set = (self.web_pull_cooldown_set + 1).min(10);
+        cooldown
+    }
+    // The current data is kept, so skins still have something to show if the pull fails
+    pub fn request_repull(&mut self) {
+        self.web_pull_cooldown_until = Local::now();
+        self.web_pull_cooldown_set = 2;
+        self.repull_requested = true;
+        self.splatfests = None;
+        self.releases = None;
+    }
+}
+
+// A panic while the store was locked poisons it, but the data inside is still fine
+fn lock() -> MutexGuard<'static, Option<Store>> {
+    STORE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn acquire() {
+    lock().get_or_insert_with(Store::new).refs += 1;
+}
+
+// The last instance to let go drops the data and the web client
+pub fn release() {
+    let mut store = lock();
+    if let Some(s) = store.as_mut() {
+        s.refs = s.refs.saturating_sub(1);
+        if s.refs == 0 {
+            *store = None;
+        }
+    }
+}
+
+pub fn with<R>(f: impl FnOnce(&mut Store) -> R) -> R {
+    f(lock().get_or_insert_with(Store::new))
+}
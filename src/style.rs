@@ -0,0 +1,130 @@
+use crate::{favorites::{Favorites, WeaponPreferences}, rm_structure::SalmonRunKind, theme::Theme};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LayoutMode {
+    Compact,
+    Standard,
+    Expanded,
+}
+impl LayoutMode {
+    pub fn parse(name: &str) -> Option<LayoutMode> {
+        match name.to_lowercase().as_str() {
+            "compact" => Some(LayoutMode::Compact),
+            "standard" => Some(LayoutMode::Standard),
+            "expanded" => Some(LayoutMode::Expanded),
+            _ => None,
+        }
+    }
+    pub fn name(&self) -> &str {
+        match self {
+            LayoutMode::Compact => "Compact",
+            LayoutMode::Standard => "Standard",
+            LayoutMode::Expanded => "Expanded",
+        }
+    }
+    // How many events of each schedule get drawn
+    pub fn event_limit(&self) -> usize {
+        match self {
+            LayoutMode::Compact => 2,
+            LayoutMode::Standard | LayoutMode::Expanded => usize::MAX,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Orientation {
+    Vertical,
+    Horizontal,
+}
+impl Orientation {
+    pub fn parse(name: &str) -> Option<Orientation> {
+        match name.to_lowercase().as_str() {
+            "vertical" => Some(Orientation::Vertical),
+            "horizontal" => Some(Orientation::Horizontal),
+            _ => None,
+        }
+    }
+    pub fn name(&self) -> &str {
+        match self {
+            Orientation::Vertical => "Vertical",
+            Orientation::Horizontal => "Horizontal",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum OutputMode {
+    Literal,
+    Variables,
+}
+impl OutputMode {
+    pub fn parse(name: &str) -> Option<OutputMode> {
+        match name.to_lowercase().as_str() {
+            "literal" => Some(OutputMode::Literal),
+            "variables" => Some(OutputMode::Variables),
+            _ => None,
+        }
+    }
+    pub fn name(&self) -> &str {
+        match self {
+            OutputMode::Literal => "Literal",
+            OutputMode::Variables => "Variables",
+        }
+    }
+}
+
+// Which Salmon Run shifts are shown
+#[derive(Clone, Copy, PartialEq)]
+pub enum CoopFilter {
+    All,
+    Only(SalmonRunKind),
+    // Everything, with upcoming Big Runs moved to the top
+    PinBigRuns,
+}
+impl CoopFilter {
+    pub fn parse(name: &str) -> Option<CoopFilter> {
+        match name.to_lowercase().as_str() {
+            "all" => Some(CoopFilter::All),
+            "pinbigruns" | "pinbigrun" => Some(CoopFilter::PinBigRuns),
+            other => SalmonRunKind::parse(other).map(CoopFilter::Only),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Style {
+    pub theme: Theme,
+    pub scale: f64,
+    pub layout: LayoutMode,
+    pub orientation: Orientation,
+    // Ids of the schedules drawn side by side instead of switching between them
+    pub columns: Vec<String>,
+    pub output: OutputMode,
+    // Name of the Core measure that generated meters send their bangs to
+    pub core_measure: String,
+    pub favorites: Favorites,
+    pub weapons: WeaponPreferences,
+}
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            theme: Theme::default(),
+            scale: 1.0,
+            layout: LayoutMode::Standard,
+            orientation: Orientation::Vertical,
+            columns: Vec::new(),
+            output: OutputMode::Literal,
+            core_measure: "SplatinkCore".to_string(),
+            favorites: Favorites::default(),
+            weapons: WeaponPreferences::default(),
+        }
+    }
+}
+impl Style {
+    pub fn is_column(&self, sche_id: &str) -> bool {
+        self.columns.iter().any(|c| c == sche_id)
+    }
+    pub fn command(&self, command: &str) -> String {
+        format!("!CommandMeasure {} \"{command}\"", self.core_measure)
+    }
+}
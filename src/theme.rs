@@ -0,0 +1,192 @@
+use crate::rm_write::{parse_ini, Color, StringOptions};
+
+pub const THEME_FILE_NAME: &str = "theme.ini";
+
+#[derive(Clone)]
+pub struct Theme {
+    pub background: Color,
+    pub panel: Color,
+    pub cell: Color,
+    pub time_bar: Color,
+    pub special: Color,
+    pub king: Color,
+    pub eggstra_work: Color,
+    pub favorite: Color,
+    pub highlight_text: Color,
+    pub font_color: Color,
+    pub font_face: Option<String>,
+    pub font_size: f64,
+    pub font_weight: usize,
+    pub small_font_size: f64,
+    pub small_font_weight: usize,
+    pub use_variables: bool,
+}
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            background: (50,50,50,255).into(),
+            panel: (40,40,40,255).into(),
+            cell: (30,30,30,255).into(),
+            time_bar: (150,50,50,255).into(),
+            special: (150,150,30,255).into(),
+            king: (75,50,50,255).into(),
+            eggstra_work: (40,110,120,255).into(),
+            favorite: (230,120,200,255).into(),
+            highlight_text: (150,150,50,255).into(),
+            font_color: (255,255,255,255).into(),
+            font_face: None,
+            font_size: 12_f64,
+            font_weight: 800,
+            small_font_size: 10_f64,
+            small_font_weight: 400,
+            use_variables: false,
+        }
+    }
+    pub fn light() -> Theme {
+        Theme {
+            background: (235,235,235,255).into(),
+            panel: (220,220,220,255).into(),
+            cell: (205,205,205,255).into(),
+            time_bar: (210,110,110,255).into(),
+            special: (230,210,100,255).into(),
+            king: (225,195,195,255).into(),
+            eggstra_work: (140,205,215,255).into(),
+            favorite: (200,60,150,255).into(),
+            highlight_text: (170,120,0,255).into(),
+            font_color: (20,20,20,255).into(),
+            ..Theme::dark()
+        }
+    }
+    pub fn high_contrast() -> Theme {
+        Theme {
+            background: (0,0,0,255).into(),
+            panel: (25,25,25,255).into(),
+            cell: (0,0,0,255).into(),
+            time_bar: (255,0,0,255).into(),
+            special: (255,215,0,255).into(),
+            king: (128,0,0,255).into(),
+            eggstra_work: (0,170,200,255).into(),
+            favorite: (255,0,255,255).into(),
+            highlight_text: (255,255,0,255).into(),
+            font_color: (255,255,255,255).into(),
+            font_weight: 900,
+            small_font_weight: 700,
+            ..Theme::dark()
+        }
+    }
+    pub fn preset(name: &str) -> Option<Theme> {
+        match name.to_lowercase().replace([' ', '-', '_'], "").as_str() {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "highcontrast" => Some(Theme::high_contrast()),
+            _ => None,
+        }
+    }
+
+    // Reads `Key=Value` pairs from the [Theme] section of a theme file. `Preset=` picks the
+    // base theme, which falls back to `default_preset` when the file doesn't set one
+    pub fn load(path: &str, default_preset: &str) -> Result<Theme, String> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Failed To Read Theme: {e:?}")),
+        };
+        let pairs: Vec<(String, String)> = parse_ini(&contents).into_iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("Theme"))
+            .flat_map(|(_, keys)| keys)
+            .map(|(key, value)| (key.to_lowercase(), value))
+            .collect();
+
+        let preset = pairs.iter().find(|(k, _)| k == "preset").map(|(_, v)| v.as_str()).unwrap_or(default_preset);
+        let mut theme = Theme::preset(preset).ok_or(format!("Unknown Theme Preset: {preset}"))?;
+        for (key, value) in pairs.iter() {
+            let color = || value.parse::<Color>().map_err(|e| format!("Invalid Theme Color {key}: {e}"));
+            let number = || value.parse::<f64>().map_err(|e| format!("Invalid Theme Value {key}: {e:?}"));
+            match key.as_str() {
+                "preset" => {},
+                "background" => theme.background = color()?,
+                "panel" => theme.panel = color()?,
+                "cell" => theme.cell = color()?,
+                "timebar" => theme.time_bar = color()?,
+                "special" => theme.special = color()?,
+                "king" => theme.king = color()?,
+                "eggstrawork" => theme.eggstra_work = color()?,
+                "favorite" => theme.favorite = color()?,
+                "highlighttext" => theme.highlight_text = color()?,
+                "fontcolor" => theme.font_color = color()?,
+                "fontface" => theme.font_face = Some(value.clone()).filter(|v| !v.is_empty()),
+                "fontsize" => theme.font_size = number()?,
+                "fontweight" => theme.font_weight = number()? as usize,
+                "smallfontsize" => theme.small_font_size = number()?,
+                "smallfontweight" => theme.small_font_weight = number()? as usize,
+                "usevariables" => theme.use_variables = value == "1",
+                _ => {},
+            }
+        }
+
+        if theme.use_variables {
+            for (name, color) in theme.named_colors_mut() {
+                color.variable = Some(name.to_string());
+            }
+        }
+        Ok(theme)
+    }
+
+    fn named_colors_mut(&mut self) -> [(&'static str, &mut Color); 10] {
+        [
+            ("ThemeBackground", &mut self.background),
+            ("ThemePanel", &mut self.panel),
+            ("ThemeCell", &mut self.cell),
+            ("ThemeTimeBar", &mut self.time_bar),
+            ("ThemeSpecial", &mut self.special),
+            ("ThemeKing", &mut self.king),
+            ("ThemeEggstraWork", &mut self.eggstra_work),
+            ("ThemeFavorite", &mut self.favorite),
+            ("ThemeHighlightText", &mut self.highlight_text),
+            ("ThemeFontColor", &mut self.font_color),
+        ]
+    }
+
+    // Values for the [Variables] section when the theme is emitted as variables
+    pub fn variables(&self) -> Vec<(String, String)> {
+        if !self.use_variables {
+            return Vec::new();
+        }
+        let mut ret: Vec<(String, String)> = self.clone().named_colors_mut().iter()
+            .map(|(name, color)| (name.to_string(), color.rgba()))
+            .collect();
+        ret.push(("ThemeFontFace".to_string(), self.font_face.clone().unwrap_or("Arial".to_string())));
+        ret
+    }
+
+    fn font_face(&self) -> Option<String> {
+        if self.use_variables {
+            Some("#ThemeFontFace#".to_string())
+        } else {
+            self.font_face.clone()
+        }
+    }
+
+    pub fn string_options(&self) -> StringOptions {
+        StringOptions {
+            font_color: Some(self.font_color.clone()),
+            font_face: self.font_face(),
+            font_size: Some(self.font_size),
+            font_weight: Some(self.font_weight),
+            ..StringOptions::default()
+        }
+    }
+
+    pub fn small_string_options(&self) -> StringOptions {
+        StringOptions {
+            font_size: Some(self.small_font_size),
+            font_weight: Some(self.small_font_weight),
+            ..self.string_options()
+        }
+    }
+}
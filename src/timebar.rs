@@ -0,0 +1,67 @@
+extern crate chrono;
+use self::chrono::{Duration, Local};
+use rainmeter::{api::RmApi, types::wchar_t};
+use crate::rm_structure::format_duration;
+
+// TimeBar measures only need their two timestamps, so they skip everything a Core
+// measure sets up. There's one of these for every event on the skin
+pub struct TimeBar {
+    rm_api: RmApi,
+    start_time: i64,
+    end_time: i64,
+    text: TimeBarText,
+    pub string_buffer: Vec<wchar_t>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TimeBarText {
+    // "1h 23m left", "Starts in 5m" or "Ended"
+    Countdown,
+    Percent,
+}
+impl TimeBarText {
+    pub fn parse(name: &str) -> Option<TimeBarText> {
+        match name.to_lowercase().as_str() {
+            "countdown" => Some(TimeBarText::Countdown),
+            "percent" => Some(TimeBarText::Percent),
+            _ => None,
+        }
+    }
+}
+
+impl TimeBar {
+    pub fn new(api: RmApi) -> TimeBar {
+        TimeBar {
+            rm_api: api,
+            start_time: 0,
+            end_time: 0,
+            text: TimeBarText::Countdown,
+            string_buffer: Vec::new(),
+        }
+    }
+    pub fn reload(&mut self, rm_api: RmApi) {
+        self.rm_api = rm_api;
+        self.start_time = self.rm_api.read_int("StartTime", 0) as i64;
+        self.end_time = self.rm_api.read_int("EndTime", 0) as i64;
+        let text_string = self.rm_api.read_string("Text", "Countdown", None);
+        self.text = TimeBarText::parse(&text_string).unwrap_or_else(|| {
+            self.rm_api.log(crate::rainmeter::api::LogType::Warning, format!("Unknown Text: {text_string}"));
+            TimeBarText::Countdown
+        });
+    }
+    pub fn update(&self) -> f64 {
+        if self.end_time <= self.start_time {
+            return 0.0;
+        }
+        (Local::now().timestamp() - self.start_time).max(0) as f64 / (self.end_time - self.start_time) as f64
+    }
+    pub fn get_string(&self) -> Option<String> {
+        let now = Local::now().timestamp();
+        Some(match self.text {
+            TimeBarText::Countdown if now < self.start_time => format!("Starts in {}", format_duration(Duration::seconds(self.start_time - now))),
+            TimeBarText::Countdown if now < self.end_time => format!("{} left", format_duration(Duration::seconds(self.end_time - now))),
+            TimeBarText::Countdown => "Ended".to_string(),
+            TimeBarText::Percent => format!("{:.0}%", self.update().min(1.0) * 100.0),
+        })
+    }
+}